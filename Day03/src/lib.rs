@@ -0,0 +1,3 @@
+pub mod render;
+pub mod table;
+pub mod wire;
@@ -1,41 +1,188 @@
-mod position;
-mod wire;
-
-use position::Position;
-use std::cmp;
-use std::collections::hash_map::HashMap;
+use measure_wires::render;
+use measure_wires::table::CrossingTable;
+use measure_wires::wire::{Crossing, Wire};
 use std::io::Read;
-use wire::Wire;
+use std::time::{Duration, Instant};
+
+const USAGE: &str =
+    "Usage: measure_wires [--walk] [--table FILE.csv|FILE.json] [--shared WIRES] [--draw] \
+[--size COLUMNSxROWS] [--svg FILE] < input
+       measure_wires --normalize < input
+       measure_wires --bench [MOVE_LENGTH]";
+
+/// Largest drawing printed by --draw unless --size is given
+const DRAW_SIZE: (usize, usize) = (100, 50);
+
+/// Zigzags of the first wire crossed by the straight second wire
+const BENCH_ZIGZAGS: usize = 10;
+
+/// First wire zigzagging up and down, second wire cutting through every zigzag,
+/// both with moves of `length` steps
+fn long_wires(length: usize) -> Result<(Wire, Wire), Box<dyn std::error::Error>> {
+    let zigzag = format!("R{0},U{0},R{0},D{0}", length);
+    let wire1 = vec![zigzag; BENCH_ZIGZAGS].join(",");
+    let wire2 = format!("U{},R{}", length / 2, 4 * length * BENCH_ZIGZAGS);
+    Ok((wire1.parse()?, wire2.parse()?))
+}
+
+/// Time both crossing algorithms on long wires
+fn bench(length: usize) -> Result<(), Box<dyn std::error::Error>> {
+    let (wire1, wire2) = long_wires(length)?;
+    let timed = |crossings: &dyn Fn() -> Vec<Crossing>| -> (Vec<Crossing>, Duration) {
+        let start = Instant::now();
+        let crossings = crossings();
+        (crossings, start.elapsed())
+    };
+    let (walked, walk_time) = timed(&|| wire1.crossings_by_walking(&wire2));
+    let (intersected, segment_time) = timed(&|| wire1.crossings(&wire2));
+    if walked != intersected {
+        return Err("The algorithms found different crossings".into());
+    }
+    println!(
+        "{} crossings between wires with moves of {} steps",
+        walked.len(),
+        length
+    );
+    println!("algorithm       time");
+    println!("walk      {:>8.2?}", walk_time);
+    println!("segments  {:>8.2?}", segment_time);
+    Ok(())
+}
+
+/// Crossing table as CSV or JSON, depending on the file extension
+fn save_table(table: &CrossingTable, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let content = match std::path::Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+    {
+        Some("csv") => table.to_csv(),
+        Some("json") => table.to_json(),
+        _ => {
+            return Err(format!("Unknown table format for {}, expected .csv or .json", path).into())
+        }
+    };
+    std::fs::write(path, content)?;
+    Ok(())
+}
+
+/// Size written as `COLUMNSxROWS`
+fn parse_size(s: &str) -> Option<(usize, usize)> {
+    let mut parts = s.split('x');
+    let columns = parts.next()?.parse().ok()?;
+    let rows = parts.next()?.parse().ok()?;
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((columns, rows))
+}
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut walk = false;
+    let mut table_file = None;
+    let mut shared = 3;
+    let mut draw = false;
+    let mut size = DRAW_SIZE;
+    let mut svg = None;
+    let mut normalize = false;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--walk" => walk = true,
+            "--table" => table_file = Some(args.next().ok_or(USAGE)?),
+            "--shared" => shared = args.next().ok_or(USAGE)?.parse()?,
+            "--draw" => draw = true,
+            "--size" => size = parse_size(&args.next().ok_or(USAGE)?).ok_or(USAGE)?,
+            "--svg" => svg = Some(args.next().ok_or(USAGE)?),
+            "--normalize" => normalize = true,
+            "--bench" => {
+                let length = match args.next() {
+                    Some(length) => length.parse()?,
+                    None => 10_000,
+                };
+                return bench(length);
+            }
+            _ => return Err(USAGE.into()),
+        }
+    }
     let mut input = String::new();
-    std::io::stdin().read_to_string(&mut input).unwrap();
-    let lines = input.lines().map(|s| s.to_owned()).collect::<Vec<String>>();
-    let wire1 = lines[0].parse::<Wire>()?;
-    let wire2 = lines[1].parse::<Wire>()?;
-    let mut wire1_position: HashMap<Position, usize> = HashMap::new();
-    for (index, pos) in wire1.positions().enumerate() {
-        if !wire1_position.contains_key(&pos){
-        wire1_position.insert(pos, index);}
-    }
-    let center = Position { x: 0, y: 0 };
-    let mut min_distance = None;
-    let mut min_delay = None;
-    for (index, pos) in wire2.positions().enumerate() {
-        if let Some(length_wire1) = wire1_position.get(&pos) {
-            let distance = position::manhattan_distance(&pos, &center);
-            min_distance = match min_distance {
-                None => Some(distance),
-                Some(previous_distance) => Some(cmp::min(previous_distance, distance)),
-            };
-            let delay = length_wire1 + index + 2;
-            min_delay = match min_delay {
-                None => Some(delay),
-                Some(previous_delay) => Some(cmp::min(previous_delay, delay))
+    std::io::stdin().read_to_string(&mut input)?;
+    let wires = input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(n, line)| {
+            line.parse::<Wire>()
+                .map_err(|e| format!("Line {}: {}", n + 1, e))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if normalize {
+        for (index, wire) in wires.iter().enumerate() {
+            let zero_length = wire.zero_length_moves();
+            if !zero_length.is_empty() {
+                eprintln!(
+                    "Wire {} has zero-length movements at {:?}",
+                    index, zero_length
+                );
             }
+            println!("{}", wire.normalized());
         }
+        return Ok(());
+    }
+    if wires.len() < 2 {
+        return Err(USAGE.into());
+    }
+    let table = if walk {
+        CrossingTable::build(&wires, Wire::crossings_by_walking)
+    } else {
+        CrossingTable::new(&wires)
+    };
+    if let Some(path) = table_file {
+        save_table(&table, &path)?;
+    }
+    if let Some(path) = svg {
+        std::fs::write(path, render::to_svg(&wires))?;
+    }
+    let closest = table.closest().ok_or("The wires never cross")?;
+    let fastest = table.fastest().ok_or("The wires never cross")?;
+    if wires.len() == 2 {
+        println!("The minimum distance is {}", closest.crossing.distance());
+        println!("The minimum delay is {}", fastest.crossing.delay());
+    } else {
+        println!(
+            "The minimum distance is {}, between wires {} and {}",
+            closest.crossing.distance(),
+            closest.wires.0,
+            closest.wires.1
+        );
+        println!(
+            "The minimum delay is {}, between wires {} and {}",
+            fastest.crossing.delay(),
+            fastest.wires.0,
+            fastest.wires.1
+        );
+        let junctions = table.shared_by(shared).collect::<Vec<_>>();
+        println!(
+            "{} positions are crossed by {} wires or more",
+            junctions.len(),
+            shared
+        );
+        for junction in junctions {
+            let wires = junction
+                .steps
+                .iter()
+                .map(|(wire, steps)| format!("wire {} after {} steps", wire, steps))
+                .collect::<Vec<_>>();
+            println!(
+                "  ({}, {}) at distance {}: {}",
+                junction.pos.x,
+                junction.pos.y,
+                junction.distance(),
+                wires.join(", ")
+            );
+        }
+    }
+    if draw {
+        print!("{}", render::to_ascii(&wires, size.0, size.1));
     }
-    println!("The minimum distance is {}", min_distance.unwrap());
-    println!("The minimum delay is {}", min_delay.unwrap());
     Ok(())
 }
@@ -0,0 +1,215 @@
+use crate::table::CrossingTable;
+use crate::wire::Wire;
+use geometry::{Bounds, SparseGrid, Vec2};
+use std::fmt::Write;
+
+/// Colors of the wires in SVG drawings, reused when there are more wires
+const COLORS: [&str; 6] = [
+    "crimson",
+    "royalblue",
+    "forestgreen",
+    "darkorange",
+    "purple",
+    "teal",
+];
+
+/// Width of SVG drawings, the height following the layout
+const SVG_WIDTH: usize = 800;
+
+/// Importance of a symbol, the most important one being kept when a character covers several
+fn priority(symbol: char) -> u8 {
+    match symbol {
+        'o' => 4,
+        'X' => 3,
+        '+' => 2,
+        '-' | '|' => 1,
+        _ => 0,
+    }
+}
+
+/// Symbol of every position covered by the wires, the central port and the crossings
+fn symbols(wires: &[Wire]) -> SparseGrid<char> {
+    let mut cells = SparseGrid::<(usize, char)>::new();
+    for (index, wire) in wires.iter().enumerate() {
+        let segments = wire
+            .segments()
+            .into_iter()
+            .filter(|segment| segment.length > 0)
+            .collect::<Vec<_>>();
+        let horizontal = |i: usize| segments[i].direction.offset().y == 0;
+        let path = (0..segments.len()).flat_map(|i| {
+            let line = if horizontal(i) { '-' } else { '|' };
+            let corner = i + 1 < segments.len() && horizontal(i + 1) != horizontal(i);
+            let length = segments[i].length;
+            (1..=length).map(move |k| if corner && k == length { '+' } else { line })
+        });
+        for (pos, symbol) in wire.positions().zip(path) {
+            let cell = match cells.get(pos) {
+                Some((_, 'X')) => continue,
+                Some((other, _)) if *other != index => (index, 'X'),
+                // The wire goes over itself
+                Some((_, previous)) if *previous != symbol => (index, '+'),
+                _ => (index, symbol),
+            };
+            cells.insert(pos, cell);
+        }
+    }
+    let mut symbols = cells
+        .iter()
+        .map(|(pos, (_, symbol))| (pos, *symbol))
+        .collect::<SparseGrid<_>>();
+    symbols.insert(Vec2::ZERO, 'o');
+    symbols
+}
+
+/// Wires drawn with `-` and `|`, `+` at corners, `o` at the central port and `X` at crossings
+///
+/// Layouts larger than `max_width` by `max_height` characters are scaled down by
+/// sampling, every character showing the most important symbol of the square of
+/// positions it covers.
+pub fn to_ascii(wires: &[Wire], max_width: usize, max_height: usize) -> String {
+    let symbols = symbols(wires);
+    let bounds = match symbols.bounds() {
+        Some(bounds) => bounds,
+        None => return String::new(),
+    };
+    let scale = |size: usize, max: usize| size.div_ceil(max.max(1));
+    let scale = scale(bounds.width(), max_width)
+        .max(scale(bounds.height(), max_height))
+        .max(1) as isize;
+    if scale == 1 {
+        return symbols.render(' ', |symbol| *symbol);
+    }
+    let mut sampled = SparseGrid::new();
+    for (pos, symbol) in symbols.iter() {
+        let pos = Vec2::new(
+            (pos.x - bounds.min.x) / scale,
+            (pos.y - bounds.min.y) / scale,
+        );
+        // Ties are broken on the symbol so that the drawing does not depend on the iteration order
+        if sampled
+            .get(pos)
+            .is_none_or(|kept| (priority(*kept), *kept) < (priority(*symbol), *symbol))
+        {
+            sampled.insert(pos, *symbol);
+        }
+    }
+    sampled.render(' ', |symbol| *symbol)
+}
+
+/// SVG drawing of the wires as lines, with the central port and the crossings circled
+///
+/// Its size only depends on the number of moves, not on their length, which suits large layouts.
+pub fn to_svg(wires: &[Wire]) -> String {
+    let segments = wires.iter().map(Wire::segments).collect::<Vec<_>>();
+    let corners = segments
+        .iter()
+        .flatten()
+        .map(|segment| segment.end())
+        .chain(std::iter::once(Vec2::ZERO));
+    let bounds = Bounds::from_points(corners).unwrap();
+    let size = bounds.width().max(bounds.height()) as f64;
+    let margin = (size / 20.0).ceil() as isize;
+    let (width, height) = (
+        bounds.max.x - bounds.min.x + 2 * margin,
+        bounds.max.y - bounds.min.y + 2 * margin,
+    );
+    let radius = size / 150.0;
+    let mut s = String::new();
+    writeln!(
+        s,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="{} {} {} {}">"#,
+        SVG_WIDTH,
+        SVG_WIDTH * height as usize / width as usize,
+        bounds.min.x - margin,
+        bounds.min.y - margin,
+        width,
+        height
+    )
+    .unwrap();
+    writeln!(
+        s,
+        r#"<rect x="{}" y="{}" width="{}" height="{}" fill="white"/>"#,
+        bounds.min.x - margin,
+        bounds.min.y - margin,
+        width,
+        height
+    )
+    .unwrap();
+    for (index, segments) in segments.iter().enumerate() {
+        let points = std::iter::once(Vec2::ZERO)
+            .chain(segments.iter().map(|segment| segment.end()))
+            .map(|pos| format!("{},{}", pos.x, pos.y))
+            .collect::<Vec<_>>();
+        writeln!(
+            s,
+            r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="1.5" vector-effect="non-scaling-stroke"/>"#,
+            points.join(" "),
+            COLORS[index % COLORS.len()]
+        )
+        .unwrap();
+    }
+    for junction in CrossingTable::new(wires).junctions() {
+        writeln!(
+            s,
+            r#"<circle cx="{}" cy="{}" r="{:.2}" fill="none" stroke="black" stroke-width="1" vector-effect="non-scaling-stroke"/>"#,
+            junction.pos.x, junction.pos.y, radius
+        )
+        .unwrap();
+    }
+    writeln!(
+        s,
+        r#"<circle cx="0" cy="0" r="{:.2}" fill="black"/>"#,
+        radius
+    )
+    .unwrap();
+    s.push_str("</svg>\n");
+    s
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn wires() -> Vec<Wire> {
+        ["R8,U5,L5,D3", "U7,R6,D4,L4"]
+            .iter()
+            .map(|wire| wire.parse::<Wire>().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn ascii() {
+        assert_eq!(
+            to_ascii(&wires(), 80, 40),
+            "+-----+  \n\
+             |     |  \n\
+             |  +--X-+\n\
+             |  |  | |\n\
+             | -X--+ |\n\
+             |  |    |\n\
+             |       |\n\
+             o-------+\n"
+        );
+        // Each character covers 2 by 2 positions
+        assert_eq!(
+            to_ascii(&wires(), 5, 4),
+            "+--+ \n\
+             |+-X+\n\
+             |X-+|\n\
+             o---+\n"
+        );
+    }
+
+    #[test]
+    fn svg() {
+        let svg = to_svg(&wires());
+        assert!(svg.starts_with(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="800" height="720" viewBox="-1 -8 10 9">"#
+        ));
+        assert!(svg
+            .contains(r#"<polyline points="0,0 8,0 8,-5 3,-5 3,-2" fill="none" stroke="crimson""#));
+        assert_eq!(svg.matches("<circle").count(), 3);
+        assert!(svg.ends_with("</svg>\n"));
+    }
+}
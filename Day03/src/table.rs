@@ -0,0 +1,234 @@
+use crate::wire::{Crossing, Wire};
+use geometry::Vec2;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Crossing of two wires, wires being numbered from 0 in input order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PairCrossing {
+    pub wires: (usize, usize),
+    pub crossing: Crossing,
+}
+
+/// Position crossed by several wires, with the steps each of them takes to first reach it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Junction {
+    pub pos: Vec2,
+    pub steps: BTreeMap<usize, usize>,
+}
+
+impl Junction {
+    /// Manhattan distance to the central port
+    pub fn distance(&self) -> usize {
+        self.pos.manhattan()
+    }
+
+    /// Wires crossing here, in input order
+    pub fn wires(&self) -> impl Iterator<Item = usize> + '_ {
+        self.steps.keys().copied()
+    }
+}
+
+/// Every crossing between any two wires of a set
+#[derive(Debug, Clone)]
+pub struct CrossingTable {
+    wire_count: usize,
+    pairs: Vec<PairCrossing>,
+    junctions: Vec<Junction>,
+}
+
+impl CrossingTable {
+    /// Table of the crossings found by intersecting the segments of the wires
+    pub fn new(wires: &[Wire]) -> CrossingTable {
+        CrossingTable::build(wires, Wire::crossings)
+    }
+
+    /// Table of the crossings found by `crossings` for every pair of wires
+    pub fn build(wires: &[Wire], crossings: fn(&Wire, &Wire) -> Vec<Crossing>) -> CrossingTable {
+        let mut pairs = Vec::new();
+        let mut junctions = BTreeMap::<Vec2, BTreeMap<usize, usize>>::new();
+        for (i, wire1) in wires.iter().enumerate() {
+            for (j, wire2) in wires.iter().enumerate().skip(i + 1) {
+                for crossing in crossings(wire1, wire2) {
+                    let steps = junctions.entry(crossing.pos).or_default();
+                    steps.insert(i, crossing.steps.0);
+                    steps.insert(j, crossing.steps.1);
+                    pairs.push(PairCrossing {
+                        wires: (i, j),
+                        crossing,
+                    });
+                }
+            }
+        }
+        CrossingTable {
+            wire_count: wires.len(),
+            pairs,
+            junctions: junctions
+                .into_iter()
+                .map(|(pos, steps)| Junction { pos, steps })
+                .collect(),
+        }
+    }
+
+    pub fn wire_count(&self) -> usize {
+        self.wire_count
+    }
+
+    /// Crossings of every pair of wires, pair by pair
+    pub fn pairs(&self) -> &[PairCrossing] {
+        &self.pairs
+    }
+
+    /// Positions crossed by two wires or more, sorted by position
+    pub fn junctions(&self) -> &[Junction] {
+        &self.junctions
+    }
+
+    /// Crossings of two wires, steps being given in the order of the wires asked for
+    pub fn between(&self, wire1: usize, wire2: usize) -> Vec<Crossing> {
+        self.pairs
+            .iter()
+            .filter_map(|pair| match pair.wires {
+                wires if wires == (wire1, wire2) => Some(pair.crossing),
+                wires if wires == (wire2, wire1) => Some(Crossing {
+                    pos: pair.crossing.pos,
+                    steps: (pair.crossing.steps.1, pair.crossing.steps.0),
+                }),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Crossing of any pair of wires closest to the central port
+    pub fn closest(&self) -> Option<&PairCrossing> {
+        self.pairs
+            .iter()
+            .min_by_key(|pair| (pair.crossing.distance(), pair.wires))
+    }
+
+    /// Crossing of any pair of wires reached in the fewest combined steps
+    pub fn fastest(&self) -> Option<&PairCrossing> {
+        self.pairs
+            .iter()
+            .min_by_key(|pair| (pair.crossing.delay(), pair.wires))
+    }
+
+    /// Positions crossed by at least `count` wires
+    pub fn shared_by(&self, count: usize) -> impl Iterator<Item = &Junction> {
+        self.junctions
+            .iter()
+            .filter(move |junction| junction.steps.len() >= count)
+    }
+
+    /// One line per junction, with the steps of every wire in its own column
+    pub fn to_csv(&self) -> String {
+        let mut s = String::from("x,y,distance,wires");
+        for wire in 0..self.wire_count {
+            write!(s, ",wire{}", wire).unwrap();
+        }
+        s.push('\n');
+        for junction in &self.junctions {
+            write!(
+                s,
+                "{},{},{},{}",
+                junction.pos.x,
+                junction.pos.y,
+                junction.distance(),
+                junction.steps.len()
+            )
+            .unwrap();
+            for wire in 0..self.wire_count {
+                s.push(',');
+                if let Some(steps) = junction.steps.get(&wire) {
+                    write!(s, "{}", steps).unwrap();
+                }
+            }
+            s.push('\n');
+        }
+        s
+    }
+
+    /// JSON array of the junctions, one per line
+    pub fn to_json(&self) -> String {
+        let junctions = self
+            .junctions
+            .iter()
+            .map(|junction| {
+                let steps = junction
+                    .steps
+                    .iter()
+                    .map(|(wire, steps)| format!(r#"{{"wire":{},"steps":{}}}"#, wire, steps))
+                    .collect::<Vec<_>>();
+                format!(
+                    r#"  {{"x":{},"y":{},"distance":{},"wires":[{}]}}"#,
+                    junction.pos.x,
+                    junction.pos.y,
+                    junction.distance(),
+                    steps.join(",")
+                )
+            })
+            .collect::<Vec<_>>();
+        if junctions.is_empty() {
+            return "[]\n".to_string();
+        }
+        format!("[\n{}\n]\n", junctions.join(",\n"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn table() -> CrossingTable {
+        let wires = ["R8,U5,L5,D3", "U7,R6,D4,L4", "R1,U3,R7"]
+            .iter()
+            .map(|wire| wire.parse::<Wire>().unwrap())
+            .collect::<Vec<_>>();
+        let table = CrossingTable::new(&wires);
+        assert_eq!(
+            table.pairs(),
+            CrossingTable::build(&wires, Wire::crossings_by_walking).pairs()
+        );
+        table
+    }
+
+    #[test]
+    fn three_wires() {
+        let table = table();
+        assert_eq!(table.wire_count(), 3);
+        let first_pair = table.between(0, 1);
+        assert_eq!(first_pair.len(), 2);
+        assert_eq!(first_pair.iter().map(Crossing::distance).min(), Some(6));
+        assert_eq!(first_pair.iter().map(Crossing::delay).min(), Some(30));
+        assert_eq!(
+            table.between(1, 0)[0].steps,
+            (first_pair[0].steps.1, first_pair[0].steps.0)
+        );
+
+        let closest = table.closest().unwrap();
+        assert_eq!(closest.wires, (0, 2));
+        assert_eq!(closest.crossing.pos, Vec2::new(1, 0));
+        assert_eq!(table.fastest().unwrap().crossing.delay(), 2);
+
+        let shared = table.shared_by(3).collect::<Vec<_>>();
+        assert_eq!(shared.len(), 1);
+        assert_eq!(shared[0].pos, Vec2::new(3, -3));
+        assert_eq!(shared[0].wires().collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert_eq!(shared[0].steps[&2], 6);
+    }
+
+    #[test]
+    fn export() {
+        let table = table();
+        let csv = table.to_csv();
+        assert!(csv.starts_with("x,y,distance,wires,wire0,wire1,wire2\n"));
+        assert!(csv.contains("\n1,0,1,2,1,,1\n"));
+        assert!(csv.contains("\n3,-3,6,3,20,20,6\n"));
+        assert_eq!(csv.lines().count(), table.junctions().len() + 1);
+
+        let json = table.to_json();
+        assert!(json.starts_with("[\n  {\"x\":1,\"y\":0,\"distance\":1,\"wires\":[{\"wire\":0,\"steps\":1},{\"wire\":2,\"steps\":1}]},\n"));
+        assert!(json.ends_with("}\n]\n"));
+        assert_eq!(CrossingTable::new(&[]).to_json(), "[]\n");
+    }
+}
@@ -1,9 +1,9 @@
-use super::{Movement, Direction};
-use crate::position::Position;
+use super::Movement;
+use geometry::Vec2;
 use std::iter::Iterator;
 
 pub struct WireIterator<'a> {
-    pos: Position,
+    pos: Vec2,
     movement_index: usize,
     distance_index: usize,
     path: &'a [Movement],
@@ -12,7 +12,7 @@ pub struct WireIterator<'a> {
 impl WireIterator<'_> {
     pub fn new<'a>(path: &'a [Movement]) -> WireIterator<'a> {
         WireIterator {
-            pos: Position { x: 0, y: 0 },
+            pos: Vec2::ZERO,
             movement_index: 0,
             distance_index: 0,
             path,
@@ -21,27 +21,18 @@ impl WireIterator<'_> {
 }
 
 impl Iterator for WireIterator<'_> {
-    type Item = Position;
+    type Item = Vec2;
     fn next(&mut self) -> Option<Self::Item> {
+        // Zero-length movements do not move the wire
+        while self
+            .path
+            .get(self.movement_index)
+            .is_some_and(|movement| movement.distance == 0)
+        {
+            self.movement_index += 1;
+        }
         if let Some(movement) = self.path.get(self.movement_index) {
-            match &movement.direction {
-                Direction::Up => self.pos = Position {
-                    x: self.pos.x,
-                    y: self.pos.y + 1
-                },
-                Direction::Down => self.pos = Position {
-                    x: self.pos.x,
-                    y: self.pos.y - 1
-                },
-                Direction::Left => self.pos = Position {
-                    x: self.pos.x - 1,
-                    y: self.pos.y
-                },
-                Direction::Right => self.pos = Position {
-                    x: self.pos.x + 1,
-                    y: self.pos.y
-                },
-            }
+            self.pos = movement.direction.forward(self.pos);
             self.distance_index += 1;
             if self.distance_index >= movement.distance {
                 self.distance_index = 0;
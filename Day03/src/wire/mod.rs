@@ -1,55 +1,107 @@
+use geometry::{Direction, Vec2};
+use std::collections::HashMap;
 use std::fmt;
 use std::str::FromStr;
 
 mod iterator;
+mod segment;
 
 use iterator::WireIterator;
+pub use segment::Segment;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Wire {
     path: Vec<Movement>,
 }
 
-#[derive(Debug)]
-pub enum ParseWireError {
-    InvalidMovement(String),
+/// Position where two wires meet, with the steps each wire takes to first reach it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Crossing {
+    pub pos: Vec2,
+    pub steps: (usize, usize),
 }
 
-impl std::error::Error for ParseWireError {}
+impl Crossing {
+    /// Manhattan distance to the central port
+    pub fn distance(&self) -> usize {
+        self.pos.manhattan()
+    }
 
-impl fmt::Display for ParseWireError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            ParseWireError::InvalidMovement(s) => write!(f, "Invalid Movement: {}", s),
-        }
+    /// Steps taken by both wires to reach the crossing
+    pub fn delay(&self) -> usize {
+        self.steps.0 + self.steps.1
     }
 }
 
-impl From<MovementError> for ParseWireError {
-    fn from(e: MovementError) -> ParseWireError {
-        match e {
-            MovementError::InvalidMovement(s) => ParseWireError::InvalidMovement(s),
+/// Keep the fewest steps each wire takes to reach `pos`
+fn record_crossing(
+    crossings: &mut HashMap<Vec2, (usize, usize)>,
+    pos: Vec2,
+    steps: (usize, usize),
+) {
+    let fewest = crossings.entry(pos).or_insert(steps);
+    *fewest = (fewest.0.min(steps.0), fewest.1.min(steps.1));
+}
+
+fn sorted_crossings(crossings: HashMap<Vec2, (usize, usize)>) -> Vec<Crossing> {
+    let mut crossings = crossings
+        .into_iter()
+        .map(|(pos, steps)| Crossing { pos, steps })
+        .collect::<Vec<_>>();
+    crossings.sort_unstable_by_key(|c| c.pos);
+    crossings
+}
+
+/// Movement of a wire that could not be read, movements being numbered from 0
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseWireError {
+    pub index: usize,
+    pub token: String,
+    pub reason: MovementError,
+}
+
+impl ParseWireError {
+    /// Character the movement could not be read at, if any
+    pub fn character(&self) -> Option<char> {
+        match self.reason {
+            MovementError::InvalidDirection(c)
+            | MovementError::InvalidDigit { character: c, .. } => Some(c),
+            _ => None,
         }
     }
 }
 
-#[derive(Debug)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
+impl std::error::Error for ParseWireError {}
+
+impl fmt::Display for ParseWireError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Invalid movement {} {:?}: {}",
+            self.index, self.token, self.reason
+        )
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Movement {
     direction: Direction,
     distance: usize,
 }
 
-#[derive(Debug)]
+/// Why a movement could not be read
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum MovementError {
-    InvalidMovement(String),
+    /// Nothing between two commas
+    Empty,
+    /// Direction other than U, D, L or R
+    InvalidDirection(char),
+    /// Direction without a distance after it
+    MissingDistance,
+    /// Character of the distance that is not a digit, `offset` counting characters from the direction
+    InvalidDigit { offset: usize, character: char },
+    /// Distance too large to be stored
+    DistanceOverflow,
 }
 
 impl std::error::Error for MovementError {}
@@ -57,7 +109,17 @@ impl std::error::Error for MovementError {}
 impl fmt::Display for MovementError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            MovementError::InvalidMovement(s) => write!(f, "Invalid Movement: {}", s),
+            MovementError::Empty => write!(f, "empty movement"),
+            MovementError::InvalidDirection(c) => {
+                write!(f, "invalid direction {:?}, expected U, D, L or R", c)
+            }
+            MovementError::MissingDistance => write!(f, "missing distance after the direction"),
+            MovementError::InvalidDigit { offset, character } => write!(
+                f,
+                "invalid digit {:?} at character {} of the distance",
+                character, offset
+            ),
+            MovementError::DistanceOverflow => write!(f, "distance too large"),
         }
     }
 }
@@ -68,8 +130,18 @@ impl FromStr for Wire {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         Ok(Wire {
             path: s
-                .split(",")
-                .map(|movement| movement.trim().parse::<Movement>())
+                .split(',')
+                .enumerate()
+                .map(|(index, token)| {
+                    token
+                        .trim()
+                        .parse::<Movement>()
+                        .map_err(|reason| ParseWireError {
+                            index,
+                            token: token.trim().to_owned(),
+                            reason,
+                        })
+                })
                 .collect::<Result<Vec<_>, _>>()?,
         })
     }
@@ -81,32 +153,58 @@ impl FromStr for Movement {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut chars = s.chars();
         let direction = match chars.next() {
-            Some(d) => d,
-            None => return Err(MovementError::InvalidMovement(s.to_owned())),
+            Some('U') => Direction::Up,
+            Some('D') => Direction::Down,
+            Some('L') => Direction::Left,
+            Some('R') => Direction::Right,
+            Some(c) => return Err(MovementError::InvalidDirection(c)),
+            None => return Err(MovementError::Empty),
         };
-        let distance = match chars.collect::<String>().parse::<usize>() {
-            Ok(v) => v,
-            Err(_) => return Err(MovementError::InvalidMovement(s.to_owned())),
+        let distance = chars.as_str();
+        if distance.is_empty() {
+            return Err(MovementError::MissingDistance);
+        }
+        if let Some((offset, character)) = distance
+            .chars()
+            .enumerate()
+            .find(|(_, c)| !c.is_ascii_digit())
+        {
+            return Err(MovementError::InvalidDigit {
+                offset: offset + 1,
+                character,
+            });
+        }
+        let distance = distance
+            .parse::<usize>()
+            .map_err(|_| MovementError::DistanceOverflow)?;
+        Ok(Movement {
+            direction,
+            distance,
+        })
+    }
+}
+
+impl fmt::Display for Movement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let direction = match self.direction {
+            Direction::Up => 'U',
+            Direction::Down => 'D',
+            Direction::Left => 'L',
+            Direction::Right => 'R',
         };
-        match direction {
-            'U' => Ok(Movement {
-                direction: Direction::Up,
-                distance,
-            }),
-            'D' => Ok(Movement {
-                direction: Direction::Down,
-                distance,
-            }),
-            'L' => Ok(Movement {
-                direction: Direction::Left,
-                distance,
-            }),
-            'R' => Ok(Movement {
-                direction: Direction::Right,
-                distance,
-            }),
-            _ => Err(MovementError::InvalidMovement(s.to_owned())),
+        write!(f, "{}{}", direction, self.distance)
+    }
+}
+
+impl fmt::Display for Wire {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, movement) in self.path.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{}", movement)?;
         }
+        Ok(())
     }
 }
 
@@ -114,4 +212,194 @@ impl Wire {
     pub fn positions<'a>(&'a self) -> WireIterator<'a> {
         WireIterator::new(&self.path)
     }
+
+    /// Straight parts of the wire, one per movement, from the central port
+    pub fn segments(&self) -> Vec<Segment> {
+        let mut start = Vec2::ZERO;
+        let mut steps = 0;
+        self.path
+            .iter()
+            .map(|movement| {
+                let segment = Segment {
+                    start,
+                    direction: movement.direction,
+                    length: movement.distance,
+                    steps,
+                };
+                start = segment.end();
+                steps += movement.distance;
+                segment
+            })
+            .collect()
+    }
+
+    /// Indices of the movements that do not move the wire
+    pub fn zero_length_moves(&self) -> Vec<usize> {
+        self.path
+            .iter()
+            .enumerate()
+            .filter(|(_, movement)| movement.distance == 0)
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Same path without zero-length movements, consecutive movements in the same direction being merged
+    pub fn normalized(&self) -> Wire {
+        let mut path = Vec::<Movement>::with_capacity(self.path.len());
+        for movement in self.path.iter().filter(|movement| movement.distance > 0) {
+            match path.last_mut() {
+                Some(last) if last.direction == movement.direction => {
+                    last.distance += movement.distance
+                }
+                _ => path.push(*movement),
+            }
+        }
+        Wire { path }
+    }
+
+    /// Crossings with another wire sorted by position, found by intersecting their segments
+    ///
+    /// Only the segments and the crossings are kept in memory, however long the moves are.
+    pub fn crossings(&self, other: &Wire) -> Vec<Crossing> {
+        let others = other.segments();
+        let mut crossings = HashMap::new();
+        for segment in self.segments() {
+            for other in &others {
+                if let Some(shared) = segment.intersection(other) {
+                    for pos in shared.positions() {
+                        let steps = (segment.steps_to(pos), other.steps_to(pos));
+                        record_crossing(&mut crossings, pos, steps);
+                    }
+                }
+            }
+        }
+        sorted_crossings(crossings)
+    }
+
+    /// Crossings with another wire sorted by position, found by walking every position of both wires
+    pub fn crossings_by_walking(&self, other: &Wire) -> Vec<Crossing> {
+        let mut first_steps = HashMap::new();
+        for (index, pos) in self.positions().enumerate() {
+            first_steps.entry(pos).or_insert(index + 1);
+        }
+        let mut crossings = HashMap::new();
+        for (index, pos) in other.positions().enumerate() {
+            if let Some(steps) = first_steps.get(&pos) {
+                record_crossing(&mut crossings, pos, (*steps, index + 1));
+            }
+        }
+        sorted_crossings(crossings)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn crossings(wire1: &str, wire2: &str) -> Vec<Crossing> {
+        let wire1 = wire1.parse::<Wire>().unwrap();
+        let wire2 = wire2.parse::<Wire>().unwrap();
+        let crossings = wire1.crossings(&wire2);
+        assert_eq!(crossings, wire1.crossings_by_walking(&wire2));
+        crossings
+    }
+
+    #[test]
+    fn parse_errors() {
+        let error = |wire: &str| wire.parse::<Wire>().unwrap_err();
+        let e = error("R8,U5,X5,D3");
+        assert_eq!((e.index, e.token.as_str()), (2, "X5"));
+        assert_eq!(e.reason, MovementError::InvalidDirection('X'));
+        assert_eq!(e.character(), Some('X'));
+        assert_eq!(
+            e.to_string(),
+            "Invalid movement 2 \"X5\": invalid direction 'X', expected U, D, L or R"
+        );
+
+        let e = error("R8, U5a");
+        assert_eq!((e.index, e.token.as_str()), (1, "U5a"));
+        assert_eq!(
+            e.reason,
+            MovementError::InvalidDigit {
+                offset: 2,
+                character: 'a'
+            }
+        );
+        assert_eq!(error("R8,,U5").reason, MovementError::Empty);
+        assert_eq!(error("R8,U").reason, MovementError::MissingDistance);
+        assert_eq!(error("R-8").character(), Some('-'));
+        assert_eq!(
+            error("R99999999999999999999999").reason,
+            MovementError::DistanceOverflow
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        for s in &["R8,U5,L5,D3", "U0,D12,R0,L7"] {
+            let wire = s.parse::<Wire>().unwrap();
+            assert_eq!(wire.to_string(), *s);
+            assert_eq!(wire.to_string().parse::<Wire>().unwrap(), wire);
+        }
+        assert_eq!(" R8 , U5".parse::<Wire>().unwrap().to_string(), "R8,U5");
+    }
+
+    #[test]
+    fn normalize() {
+        let wire = "R2,R3,U0,R1,D4,D0,D1,L2,R2".parse::<Wire>().unwrap();
+        assert_eq!(wire.zero_length_moves(), vec![2, 5]);
+        let normalized = wire.normalized();
+        assert_eq!(normalized.to_string(), "R6,D5,L2,R2");
+        assert!(normalized.zero_length_moves().is_empty());
+        assert_eq!(
+            normalized.positions().collect::<Vec<_>>(),
+            wire.positions().collect::<Vec<_>>()
+        );
+        assert_eq!(normalized.normalized(), normalized);
+    }
+
+    #[test]
+    fn examples() {
+        let examples = [
+            ("R8,U5,L5,D3", "U7,R6,D4,L4", 6, 30),
+            (
+                "R75,D30,R83,U83,L12,D49,R71,U7,L72",
+                "U62,R66,U55,R34,D71,R55,D58,R83",
+                159,
+                610,
+            ),
+            (
+                "R98,U47,R26,D63,R33,U87,L62,D20,R33,U53,R51",
+                "U98,R91,D20,R16,D67,R40,U7,R15,U6,R7",
+                135,
+                410,
+            ),
+        ];
+        for (wire1, wire2, distance, delay) in examples.iter() {
+            let crossings = crossings(wire1, wire2);
+            assert_eq!(
+                crossings.iter().map(Crossing::distance).min(),
+                Some(*distance)
+            );
+            assert_eq!(crossings.iter().map(Crossing::delay).min(), Some(*delay));
+        }
+    }
+
+    #[test]
+    fn overlaps_and_loops() {
+        // Both wires run along y = 0 from x = 2 to x = 4, the first one passing twice at (2, 0)
+        let crossings = crossings("R4,U1,L2,D1,R0,D1", "U1,R2,D1,R3");
+        let positions = crossings.iter().map(|c| c.pos).collect::<Vec<_>>();
+        assert_eq!(
+            positions,
+            vec![
+                Vec2::new(2, -1),
+                Vec2::new(2, 0),
+                Vec2::new(3, 0),
+                Vec2::new(4, 0)
+            ]
+        );
+        assert_eq!(crossings[1].steps, (2, 4));
+        assert_eq!(crossings[3].steps, (4, 6));
+    }
 }
@@ -0,0 +1,79 @@
+use geometry::{Bounds, Direction, Vec2};
+
+/// Straight part of a wire, covering the `length` positions after `start`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Segment {
+    pub start: Vec2,
+    pub direction: Direction,
+    pub length: usize,
+    /// Steps taken by the wire to reach `start`
+    pub steps: usize,
+}
+
+impl Segment {
+    pub fn end(&self) -> Vec2 {
+        self.start + self.direction.offset() * self.length as isize
+    }
+
+    /// Rectangle of the positions covered, None for a zero-length segment
+    pub fn covered(&self) -> Option<Bounds> {
+        if self.length == 0 {
+            return None;
+        }
+        let first = self.direction.forward(self.start);
+        Bounds::from_points(vec![first, self.end()])
+    }
+
+    /// Steps taken by the wire to reach `pos` along this segment
+    pub fn steps_to(&self, pos: Vec2) -> usize {
+        self.steps + self.start.manhattan_distance(pos)
+    }
+
+    /// Positions covered by both segments
+    ///
+    /// Segments being straight, the positions covered by both of them form a
+    /// single rectangle: a point when they cross and a line when they overlap.
+    pub fn intersection(&self, other: &Segment) -> Option<Bounds> {
+        self.covered()?.intersection(&other.covered()?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn segment(x: isize, y: isize, direction: Direction, length: usize) -> Segment {
+        Segment {
+            start: Vec2::new(x, y),
+            direction,
+            length,
+            steps: 10,
+        }
+    }
+
+    #[test]
+    fn intersection() {
+        let right = segment(0, 0, Direction::Right, 5);
+        let up = segment(3, 2, Direction::Up, 4);
+        let crossing = right.intersection(&up).unwrap();
+        assert_eq!(
+            (crossing.min, crossing.max),
+            (Vec2::new(3, 0), Vec2::new(3, 0))
+        );
+        assert_eq!(right.steps_to(crossing.min), 13);
+        assert_eq!(up.steps_to(crossing.min), 12);
+
+        // Start positions belong to the previous segment
+        assert_eq!(right.intersection(&segment(0, 2, Direction::Up, 4)), None);
+        assert_eq!(right.intersection(&segment(5, 0, Direction::Down, 4)), None);
+
+        let overlap = right
+            .intersection(&segment(7, 0, Direction::Left, 4))
+            .unwrap();
+        assert_eq!(
+            (overlap.min, overlap.max),
+            (Vec2::new(3, 0), Vec2::new(5, 0))
+        );
+        assert_eq!(right.intersection(&segment(2, 0, Direction::Left, 0)), None);
+    }
+}
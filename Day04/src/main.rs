@@ -1,52 +1,46 @@
-fn number_to_digits(n: u32) -> Vec<u32> {
-    n.to_string()
-        .chars()
-        .map(|c| c.to_digit(10).unwrap())
-        .collect()
-}
+use crack_secure_container::rules::{Policy, Rule};
 
-fn is_password_valid(pass: u32) -> bool {
-    let digits = number_to_digits(pass);
-    if digits.len() == 6 {
-        let mut pair_found = false;
-        let mut decrease = false;
-        for i in 0..(digits.len() - 1) {
-            if digits[i] > digits[i + 1] {
-                decrease = true;
-                break;
-            }
-            if digits[i] == digits[i + 1]
-                && (i == 0 || digits[i] != digits[i - 1])
-                && (i == digits.len() - 2 || digits[i] != digits[i + 2])
-            {
-                pair_found = true;
-            }
-        }
-        pair_found && !decrease
-    } else {
-        false
-    }
-}
+const USAGE: &str = "Usage: crack_secure_container LOW-HIGH [--part 1|2] [--rule RULE]...
+Rules: length=N, non-decreasing, non-increasing, increasing, decreasing,
+       run=MIN[..[MAX]], only=DIGITS, some=DIGITS, !RULE";
 
-fn main() {
-    let mut valid_passwords_count = 0;
-    for i in 178416..=676461 {
-        if is_password_valid(i) {
-            valid_passwords_count += 1;
-        }
-    }
-    println!("Number of valid passwords: {}", valid_passwords_count);
+/// Range written as `LOW-HIGH`, both included
+fn parse_range(s: &str) -> Option<(u32, u32)> {
+    let (low, high) = s.split_once('-')?;
+    Some((low.parse().ok()?, high.parse().ok()?))
 }
 
-#[cfg(test)]
-mod test {
-    use super::*;
-
-    #[test]
-    fn password_validity() {
-        assert!(is_password_valid(111111));
-        assert!(!is_password_valid(1111111));
-        assert!(!is_password_valid(223450));
-        assert!(!is_password_valid(123789));
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut range = None;
+    let mut part = None;
+    let mut rules = Vec::new();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--part" => part = Some(args.next().ok_or(USAGE)?),
+            "--rule" => rules.push(args.next().ok_or(USAGE)?.parse::<Rule>()?),
+            _ => range = Some(parse_range(&arg).ok_or(USAGE)?),
+        }
     }
+    let (low, high) = range.ok_or(USAGE)?;
+    let policy = match part.as_deref() {
+        Some("1") => Policy::part1(),
+        Some("2") => Policy::part2(),
+        Some(_) => return Err(USAGE.into()),
+        None if rules.is_empty() => {
+            println!(
+                "Number of valid passwords for part 1: {}",
+                Policy::part1().count(low..=high)
+            );
+            println!(
+                "Number of valid passwords for part 2: {}",
+                Policy::part2().count(low..=high)
+            );
+            return Ok(());
+        }
+        None => Policy::new(),
+    };
+    let policy = rules.into_iter().fold(policy, Policy::rule);
+    println!("Number of valid passwords: {}", policy.count(low..=high));
+    Ok(())
 }
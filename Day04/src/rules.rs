@@ -0,0 +1,298 @@
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+/// Digits of a number, most significant first
+pub fn number_to_digits(n: u32) -> Vec<u32> {
+    n.to_string()
+        .chars()
+        .map(|c| c.to_digit(10).unwrap())
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuleError {
+    UnknownRule(String),
+    InvalidValue(String),
+}
+
+impl std::error::Error for RuleError {}
+
+impl fmt::Display for RuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuleError::UnknownRule(s) => write!(f, "Unknown rule: {}", s),
+            RuleError::InvalidValue(s) => write!(f, "Invalid rule value: {}", s),
+        }
+    }
+}
+
+/// Set of decimal digits
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DigitSet(u16);
+
+impl DigitSet {
+    pub fn new(digits: &[u32]) -> DigitSet {
+        DigitSet(digits.iter().fold(0, |set, d| set | 1 << d))
+    }
+
+    pub fn contains(self, digit: u32) -> bool {
+        digit < 10 && self.0 & 1 << digit != 0
+    }
+}
+
+/// Digits written one after the other, like `13579`
+impl FromStr for DigitSet {
+    type Err = RuleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let digits = s
+            .chars()
+            .map(|c| c.to_digit(10))
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| RuleError::InvalidValue(s.to_owned()))?;
+        Ok(DigitSet::new(&digits))
+    }
+}
+
+/// How each digit compares to the one before it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Order {
+    NonDecreasing,
+    NonIncreasing,
+    Increasing,
+    Decreasing,
+}
+
+impl Order {
+    fn holds(self, previous: u32, digit: u32) -> bool {
+        match self {
+            Order::NonDecreasing => previous <= digit,
+            Order::NonIncreasing => previous >= digit,
+            Order::Increasing => previous < digit,
+            Order::Decreasing => previous > digit,
+        }
+    }
+}
+
+/// Condition on the digits of a password
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Rule {
+    /// Exact number of digits
+    Length(usize),
+    /// Order of every two adjacent digits
+    Monotonic(Order),
+    /// At least one group of adjacent identical digits, between `min` and `max` digits long
+    Run {
+        min: usize,
+        max: Option<usize>,
+    },
+    /// Every digit is in the set
+    OnlyDigits(DigitSet),
+    /// At least one digit is in the set
+    SomeDigit(DigitSet),
+    All(Vec<Rule>),
+    Any(Vec<Rule>),
+    Not(Box<Rule>),
+}
+
+/// Lengths of the groups of adjacent identical digits
+fn runs(digits: &[u32]) -> Vec<usize> {
+    let mut runs = Vec::new();
+    let mut i = 0;
+    while i < digits.len() {
+        let run = digits[i..].iter().take_while(|d| **d == digits[i]).count();
+        runs.push(run);
+        i += run;
+    }
+    runs
+}
+
+impl Rule {
+    pub fn check(&self, digits: &[u32]) -> bool {
+        match self {
+            Rule::Length(length) => digits.len() == *length,
+            Rule::Monotonic(order) => digits.windows(2).all(|w| order.holds(w[0], w[1])),
+            Rule::Run { min, max } => runs(digits)
+                .into_iter()
+                .any(|run| run >= *min && max.is_none_or(|max| run <= max)),
+            Rule::OnlyDigits(set) => digits.iter().all(|d| set.contains(*d)),
+            Rule::SomeDigit(set) => digits.iter().any(|d| set.contains(*d)),
+            Rule::All(rules) => rules.iter().all(|rule| rule.check(digits)),
+            Rule::Any(rules) => rules.iter().any(|rule| rule.check(digits)),
+            Rule::Not(rule) => !rule.check(digits),
+        }
+    }
+}
+
+/// Group length written as `N`, `MIN..` or `MIN..MAX`
+fn parse_run(s: &str) -> Result<Rule, RuleError> {
+    let invalid = || RuleError::InvalidValue(s.to_owned());
+    let (min, max) = match s.split_once("..") {
+        Some((min, "")) => (min.parse().map_err(|_| invalid())?, None),
+        Some((min, max)) => (
+            min.parse().map_err(|_| invalid())?,
+            Some(max.parse().map_err(|_| invalid())?),
+        ),
+        None => {
+            let length = s.parse().map_err(|_| invalid())?;
+            (length, Some(length))
+        }
+    };
+    if min == 0 || max.is_some_and(|max| max < min) {
+        return Err(invalid());
+    }
+    Ok(Rule::Run { min, max })
+}
+
+/// Rule written as `length=N`, `non-decreasing`, `non-increasing`, `increasing`,
+/// `decreasing`, `run=MIN[..[MAX]]`, `only=DIGITS` or `some=DIGITS`, negated by a leading `!`
+impl FromStr for Rule {
+    type Err = RuleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(rule) = s.strip_prefix('!') {
+            return Ok(Rule::Not(Box::new(rule.parse()?)));
+        }
+        let (name, value) = match s.split_once('=') {
+            Some((name, value)) => (name, Some(value)),
+            None => (s, None),
+        };
+        match (name, value) {
+            ("length", Some(length)) => Ok(Rule::Length(
+                length
+                    .parse()
+                    .map_err(|_| RuleError::InvalidValue(length.to_owned()))?,
+            )),
+            ("non-decreasing", None) => Ok(Rule::Monotonic(Order::NonDecreasing)),
+            ("non-increasing", None) => Ok(Rule::Monotonic(Order::NonIncreasing)),
+            ("increasing", None) => Ok(Rule::Monotonic(Order::Increasing)),
+            ("decreasing", None) => Ok(Rule::Monotonic(Order::Decreasing)),
+            ("run", Some(run)) => parse_run(run),
+            ("only", Some(digits)) => Ok(Rule::OnlyDigits(digits.parse()?)),
+            ("some", Some(digits)) => Ok(Rule::SomeDigit(digits.parse()?)),
+            _ => Err(RuleError::UnknownRule(s.to_owned())),
+        }
+    }
+}
+
+/// Rules a password must all follow
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Policy {
+    rules: Vec<Rule>,
+}
+
+impl Policy {
+    pub fn new() -> Policy {
+        Policy::default()
+    }
+
+    pub fn rule(mut self, rule: Rule) -> Policy {
+        self.rules.push(rule);
+        self
+    }
+
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    /// Six digits never decreasing, with at least two adjacent identical digits
+    pub fn part1() -> Policy {
+        Policy::new()
+            .rule(Rule::Length(6))
+            .rule(Rule::Monotonic(Order::NonDecreasing))
+            .rule(Rule::Run { min: 2, max: None })
+    }
+
+    /// Six digits never decreasing, with a group of exactly two identical digits
+    pub fn part2() -> Policy {
+        Policy::new()
+            .rule(Rule::Length(6))
+            .rule(Rule::Monotonic(Order::NonDecreasing))
+            .rule(Rule::Run {
+                min: 2,
+                max: Some(2),
+            })
+    }
+
+    pub fn is_valid(&self, password: u32) -> bool {
+        let digits = number_to_digits(password);
+        self.rules.iter().all(|rule| rule.check(&digits))
+    }
+
+    /// Number of valid passwords in the range
+    pub fn count(&self, range: RangeInclusive<u32>) -> usize {
+        range.filter(|password| self.is_valid(*password)).count()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn password_validity() {
+        let part1 = Policy::part1();
+        assert!(part1.is_valid(111111));
+        assert!(!part1.is_valid(1111111));
+        assert!(!part1.is_valid(223450));
+        assert!(!part1.is_valid(123789));
+
+        let part2 = Policy::part2();
+        assert!(part2.is_valid(112233));
+        assert!(!part2.is_valid(123444));
+        assert!(part2.is_valid(111122));
+        assert!(!part2.is_valid(111111));
+    }
+
+    #[test]
+    fn rules() {
+        let digits = number_to_digits(135599);
+        assert_eq!(runs(&digits), vec![1, 1, 2, 2]);
+        assert!(Rule::OnlyDigits("13579".parse().unwrap()).check(&digits));
+        assert!(!Rule::SomeDigit("02468".parse().unwrap()).check(&digits));
+        assert!(!Rule::Monotonic(Order::Increasing).check(&digits));
+        assert!(Rule::Any(vec![Rule::Length(5), Rule::Run { min: 2, max: None }]).check(&digits));
+        assert!(!Rule::All(vec![Rule::Length(6), Rule::Run { min: 3, max: None }]).check(&digits));
+
+        let policy = Policy::new()
+            .rule("length=4".parse().unwrap())
+            .rule("decreasing".parse().unwrap())
+            .rule("!some=0".parse().unwrap());
+        assert!(policy.is_valid(9631));
+        assert!(!policy.is_valid(9630));
+        assert_eq!(policy.count(1000..=9999), 126);
+        assert_eq!(Policy::part1().count(111110..=111123), 11);
+    }
+
+    #[test]
+    fn parse_rules() {
+        assert_eq!(
+            "run=2".parse(),
+            Ok(Rule::Run {
+                min: 2,
+                max: Some(2)
+            })
+        );
+        assert_eq!("run=3..".parse(), Ok(Rule::Run { min: 3, max: None }));
+        assert_eq!(
+            "run=2..4".parse(),
+            Ok(Rule::Run {
+                min: 2,
+                max: Some(4)
+            })
+        );
+        assert_eq!(
+            "run=4..2".parse::<Rule>(),
+            Err(RuleError::InvalidValue("4..2".to_string()))
+        );
+        assert_eq!(
+            "only=12a".parse::<Rule>(),
+            Err(RuleError::InvalidValue("12a".to_string()))
+        );
+        assert_eq!(
+            "sorted".parse::<Rule>(),
+            Err(RuleError::UnknownRule("sorted".to_string()))
+        );
+    }
+}
@@ -1,120 +1,132 @@
 use intcode_computer::*;
 use std::cell::RefCell;
+use std::collections::VecDeque;
 use std::rc::Rc;
 
+/// What an amplifier is doing after giving back control
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AmplifierState {
+    /// The program can go on without any new input
+    Running,
+    /// The program needs an input to go on
+    WaitingForInput,
+    /// The program reached its stop instruction
+    Halted,
+}
+
+/// Amplifier program running as a process, with queued inputs and outputs read one by one
 pub struct Amplifier {
-        interpreter: Interpreter,
-        input: Rc<RefCell<Option<isize>>>,
-        output: Rc<RefCell<Option<isize>>>,
-        skip_output: Rc<RefCell<bool>>
+    interpreter: Interpreter,
+    inputs: Rc<RefCell<VecDeque<isize>>>,
+    output: Rc<RefCell<Option<isize>>>,
+    state: AmplifierState,
 }
 
 impl Amplifier {
-        pub fn new(program: Program, phase: isize) -> Result<Amplifier, Box<dyn std::error::Error>> {
-
-                let mut amplifier = Amplifier {
-                        interpreter: Interpreter::new(program),
-                        input: Rc::new(RefCell::new(Some(phase))),
-                        output: Rc::new(RefCell::new(None)),
-                        skip_output: Rc::new(RefCell::new(false)),
-                };
-                let interpreter = &mut amplifier.interpreter;
-                // Instruction: add
-                interpreter.add_instruction(Instruction::new(1, 3, |mut params| {
-                        let sum = params[0].read()? + params[1].read()?;
-                        params[2].write(sum)?;
-                        Ok(InstructionResult::Continue)
-                })?);
+    pub fn new(program: Program, phase: isize) -> Result<Amplifier, Box<dyn std::error::Error>> {
+        let mut interpreter = Interpreter::without_io(program, &Dialect::standard())?;
+        let inputs = Rc::new(RefCell::new(VecDeque::new()));
+        let output = Rc::new(RefCell::new(None));
+        inputs.borrow_mut().push_back(phase);
 
-                // Instruction: mulitply
-                interpreter.add_instruction(Instruction::new(2, 3, |mut params| {
-                        let product = params[0].read()? * params[1].read()?;
-                        params[2].write(product)?;
-                        Ok(InstructionResult::Continue)
-                })?);
+        // Instruction: input, blocking while the queue is empty
+        interpreter.add_instruction(Instruction::new(3, 1, {
+            let inputs = inputs.clone();
+            move |mut params| match inputs.borrow_mut().pop_front() {
+                Some(input) => {
+                    params[0].write(input)?;
+                    Ok(InstructionResult::Continue)
+                }
+                None => Ok(InstructionResult::Block),
+            }
+        })?);
 
-                // Interpreter: jump-if-true
-                interpreter.add_instruction(Instruction::new(5, 2, |params| {
-                        if params[0].read()? != 0 {
-                                Ok(InstructionResult::JumpTo(params[1].read()? as usize))
-                        } else {
-                                Ok(InstructionResult::Continue)
-                        }
-                })?);
-                // Interpreter: jump-if-false
-                interpreter.add_instruction(Instruction::new(6, 2, |params| {
-                        if params[0].read()? == 0 {
-                                Ok(InstructionResult::JumpTo(params[1].read()? as usize))
-                        } else {
-                                Ok(InstructionResult::Continue)
-                        }
-                })?);
+        // Instruction: output, blocking until the previous output has been read
+        interpreter.add_instruction(Instruction::new(4, 1, {
+            let output = output.clone();
+            move |params| {
+                let mut output = output.borrow_mut();
+                if output.is_some() {
+                    return Ok(InstructionResult::Block);
+                }
+                *output = Some(params[0].read()?);
+                Ok(InstructionResult::Continue)
+            }
+        })?);
 
-                // Instruction: less than
-                interpreter.add_instruction(Instruction::new(7, 3, |mut params| {
-                        let result = if params[0].read()? < params[1].read()? {
-                                1
-                        } else {
-                                0
-                        };
-                        params[2].write(result)?;
-                        Ok(InstructionResult::Continue)
-                })?);
+        Ok(Amplifier {
+            interpreter,
+            inputs,
+            output,
+            state: AmplifierState::Running,
+        })
+    }
 
-                // Instruction: equals
-                interpreter.add_instruction(Instruction::new(8, 3, |mut params| {
-                        let result = if params[0].read()? == params[1].read()? {
-                                1
-                        } else {
-                                0
-                        };
-                        params[2].write(result)?;
-                        Ok(InstructionResult::Continue)
-                })?);
+    /// Run the program until its next output, or until it halts or waits for an input
+    pub fn execute(&mut self) -> Result<Option<isize>, InterpreterError> {
+        let reason = self.interpreter.run()?;
+        let output = self.output.borrow_mut().take();
+        self.state = match (output, reason) {
+            (Some(_), _) => AmplifierState::Running,
+            (None, StopReason::Blocked) => AmplifierState::WaitingForInput,
+            (None, StopReason::Halted) => AmplifierState::Halted,
+        };
+        Ok(output)
+    }
 
-                // Interpreter: quit
-                interpreter
-                        .add_instruction(Instruction::new(99, 0, |_| Ok(InstructionResult::Quit))?);
+    /// Queue an input, read by the program when it needs one
+    pub fn input(&mut self, input: isize) {
+        self.inputs.borrow_mut().push_back(input);
+    }
 
-                let output_copy = amplifier.output.clone();
-                let skip_output_copy = amplifier.skip_output.clone();
-
-                // Interpreter: output
-                interpreter.add_instruction(Instruction::new(4, 1, move |params| {
-                        let mut skip = skip_output_copy.borrow_mut();
-                        if !*skip {
-                                *output_copy.borrow_mut() = Some(params[0].read()?);
-                                *skip = true;
-                                Ok(InstructionResult::Quit)
-                        } else {
-                                *skip = false;
-                                Ok(InstructionResult::Continue)
-                        }
-                })?);
+    pub fn state(&self) -> AmplifierState {
+        self.state
+    }
+}
 
-                let input_copy = amplifier.input.clone();
-                // Instruction: input
-                interpreter.add_instruction(Instruction::new(3, 1, move |mut params| {
-                        let mut input = input_copy.borrow_mut();
-                        if let Some(input) = input.take() {
-                                params[0].write(input)?;
-                                Ok(InstructionResult::Continue)
-                        } else {
-                                Ok(InstructionResult::Quit)
-                        }
-                })?);
+#[cfg(test)]
+mod test {
+    use super::*;
 
-                Ok(amplifier)
-        }
+    #[test]
+    fn several_outputs_per_input() {
+        // Outputs each input, then twice the input
+        let program = "3,20,4,20,1002,20,2,21,4,21,1105,1,0"
+            .parse::<Program>()
+            .unwrap();
+        let mut amplifier = Amplifier::new(program, 7).unwrap();
+        assert_eq!(amplifier.execute().unwrap(), Some(7));
+        assert_eq!(amplifier.state(), AmplifierState::Running);
+        assert_eq!(amplifier.execute().unwrap(), Some(14));
+        assert_eq!(amplifier.execute().unwrap(), None);
+        assert_eq!(amplifier.state(), AmplifierState::WaitingForInput);
 
-        pub fn execute(&mut self) -> Option<isize> {
-                self.interpreter.execute().unwrap();
+        amplifier.input(3);
+        amplifier.input(4);
+        let outputs = std::iter::from_fn(|| amplifier.execute().unwrap()).collect::<Vec<_>>();
+        assert_eq!(outputs, vec![3, 6, 4, 8]);
+        assert_eq!(amplifier.state(), AmplifierState::WaitingForInput);
+    }
 
-                let result = self.output.borrow_mut().take();
-                result
-        }
+    #[test]
+    fn halt() {
+        let program = "3,5,4,5,99,0".parse::<Program>().unwrap();
+        let mut amplifier = Amplifier::new(program, 5).unwrap();
+        assert_eq!(amplifier.execute().unwrap(), Some(5));
+        assert_eq!(amplifier.execute().unwrap(), None);
+        assert_eq!(amplifier.state(), AmplifierState::Halted);
+        amplifier.input(1);
+        assert_eq!(amplifier.execute().unwrap(), None);
+        assert_eq!(amplifier.state(), AmplifierState::Halted);
+    }
 
-        pub fn input(&mut self, input: isize) {
-                *self.input.borrow_mut() = Some(input);
-        }
+    #[test]
+    fn invalid_program() {
+        let program = "3,5,4,5,42,0".parse::<Program>().unwrap();
+        let mut amplifier = Amplifier::new(program, 5).unwrap();
+        assert!(matches!(
+            amplifier.execute(),
+            Err(InterpreterError::UnknownOpcode(42, _))
+        ));
+    }
 }
@@ -0,0 +1,143 @@
+use crate::amplifier::{Amplifier, AmplifierState};
+use crate::phase::PhaseGenerator;
+use intcode_computer::{InterpreterError, Program};
+use std::fmt;
+
+/// How the amplifiers of a chain are wired
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChainMode {
+    /// The signal goes through every amplifier once
+    Series,
+    /// The last amplifier feeds the first one until the amplifiers halt
+    Feedback,
+}
+
+impl ChainMode {
+    /// Every phase setting usable for a chain of `amplifiers` amplifiers in this mode
+    pub fn phases(self, amplifiers: u8) -> Option<PhaseGenerator> {
+        match self {
+            ChainMode::Series => Some(PhaseGenerator::series(amplifiers)),
+            ChainMode::Feedback => PhaseGenerator::feedback(amplifiers),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ChainError {
+    InvalidProgram(Box<dyn std::error::Error>),
+    NoSignal(usize),
+    Execution(usize, InterpreterError),
+    TooManyAmplifiers(u8),
+}
+
+impl std::error::Error for ChainError {}
+
+impl fmt::Display for ChainError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChainError::InvalidProgram(e) => write!(f, "Invalid amplifier program: {}", e),
+            ChainError::NoSignal(i) => write!(f, "Amplifier {} halted without output", i),
+            ChainError::Execution(i, e) => write!(f, "Amplifier {} failed: {}", i, e),
+            ChainError::TooManyAmplifiers(n) => {
+                write!(f, "No phase settings for {} amplifiers in this mode", n)
+            }
+        }
+    }
+}
+
+/// Signals produced by a run of an amplifier chain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainOutput {
+    /// Last signal sent to the thrusters
+    pub thrusters: isize,
+    /// Signals output by each amplifier, in order
+    pub history: Vec<Vec<isize>>,
+}
+
+pub struct AmplifierChain {
+    program: Program,
+    phases: Vec<u8>,
+    mode: ChainMode,
+}
+
+impl AmplifierChain {
+    pub fn new(program: Program, phases: &[u8], mode: ChainMode) -> AmplifierChain {
+        AmplifierChain {
+            program,
+            phases: phases.to_vec(),
+            mode,
+        }
+    }
+
+    /// Send `input` to the first amplifier and collect the signals until the chain stops
+    pub fn run(&self, mut input: isize) -> Result<ChainOutput, ChainError> {
+        let mut amplifiers = self
+            .phases
+            .iter()
+            .map(|phase| Amplifier::new(self.program.clone(), *phase as isize))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(ChainError::InvalidProgram)?;
+        let mut history = vec![Vec::new(); amplifiers.len()];
+        loop {
+            for (i, amplifier) in amplifiers.iter_mut().enumerate() {
+                amplifier.input(input);
+                let output = amplifier
+                    .execute()
+                    .map_err(|e| ChainError::Execution(i, e))?;
+                input = match output {
+                    Some(signal) => signal,
+                    None if i == 0
+                        && !history[0].is_empty()
+                        && amplifier.state() == AmplifierState::Halted =>
+                    {
+                        return Ok(ChainOutput {
+                            thrusters: input,
+                            history,
+                        })
+                    }
+                    None => return Err(ChainError::NoSignal(i)),
+                };
+                history[i].push(input);
+            }
+            if self.mode == ChainMode::Series {
+                return Ok(ChainOutput {
+                    thrusters: input,
+                    history,
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn series() {
+        let program = "3,23,3,24,1002,24,10,24,1002,23,-1,23,\
+                       101,5,23,23,1,24,23,23,4,23,99,0,0"
+            .parse::<Program>()
+            .unwrap();
+        let chain = AmplifierChain::new(program, &[0, 1, 2, 3, 4], ChainMode::Series);
+        let output = chain.run(0).unwrap();
+        assert_eq!(output.thrusters, 54321);
+        assert_eq!(
+            output.history,
+            vec![vec![5], vec![54], vec![543], vec![5432], vec![54321]]
+        );
+    }
+
+    #[test]
+    fn feedback() {
+        let program = "3,26,1001,26,-4,26,3,27,1002,27,2,27,1,27,26,\
+                       27,4,27,1001,28,-1,28,1005,28,6,99,0,0,5"
+            .parse::<Program>()
+            .unwrap();
+        let chain = AmplifierChain::new(program, &[9, 8, 7, 6, 5], ChainMode::Feedback);
+        let output = chain.run(0).unwrap();
+        assert_eq!(output.thrusters, 139629729);
+        assert!(output.history.iter().all(|signals| signals.len() == 5));
+        assert_eq!(output.history[4].last(), Some(&139629729));
+    }
+}
@@ -1,51 +1,67 @@
 use intcode_computer::*;
 
 mod amplifier;
+mod chain;
 mod phase;
+mod report;
 
-use amplifier::Amplifier;
-use phase::PhaseGenerator;
+use chain::ChainMode;
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let file = std::env::args().skip(1).next().unwrap();
-    let input = std::fs::read_to_string(file)?;
-    let program = input.parse::<Program>()?;
+const USAGE: &str = "Usage: amplifiers <program> [--amplifiers N] [--top N] [--csv FILE] [--chart]";
 
-    let mut max = 0;
-    let mut max_phase = None;
-    for phase in PhaseGenerator::new(true) {
-        let mut input = 0;
-        let mut amplifiers = Vec::new();
-        let mut output = None;
-        for i in 0..5 {
-            let phase = phase[i] as isize;
-            amplifiers.push(Amplifier::new(program.clone(), phase)?)
-        }
-        loop {
-            for i in 0..5 {
-                let mut amplifier = &mut amplifiers[i];
-                amplifier.execute();
-                amplifier.input(input);
-                input = match amplifier.execute() {
-                    Some(o) => o,
-                    None => {
-                        output = Some(input);
-                        break;
-                    }
-                };
-            }
-            if output != None {
-                break;
-            }
-        }
-        let output = output.unwrap();
-        if output > max {
-            max = output;
-            max_phase = Some(phase);
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut file = None;
+    let mut amplifiers = 5;
+    let mut top = 1;
+    let mut csv = None;
+    let mut chart = false;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--amplifiers" => match args.next().ok_or(USAGE)?.parse::<u8>()? {
+                0 => return Err(USAGE.into()),
+                n => amplifiers = n,
+            },
+            "--top" => top = args.next().ok_or(USAGE)?.parse::<usize>()?,
+            "--csv" => csv = Some(args.next().ok_or(USAGE)?),
+            "--chart" => chart = true,
+            _ if arg.starts_with("--") || file.is_some() => return Err(USAGE.into()),
+            _ => file = Some(arg),
         }
     }
+    let input = std::fs::read_to_string(file.ok_or(USAGE)?)?;
+    let program = input.parse::<Program>()?;
+
+    let series = report::search(&program, ChainMode::Series, amplifiers)?;
+    let (max_phase, output) = &series[0];
+    println!(
+        "In series, max phase is {} with power {}",
+        report::format_phase(max_phase),
+        output.thrusters
+    );
+    let feedback = report::search(&program, ChainMode::Feedback, amplifiers)?;
+    let (max_phase, output) = &feedback[0];
+    println!(
+        "With feedback, max phase is {} with power {}",
+        report::format_phase(max_phase),
+        output.thrusters
+    );
 
-    println!("max phase is {:?} with power {}", max_phase, max);
-    //dbg!(interpreter);
+    if top > 1 {
+        println!("\nBest phases in series:");
+        print!("{}", report::ranking(&series, top));
+        println!("\nBest phases with feedback:");
+        print!("{}", report::ranking(&feedback, top));
+    }
+    if chart {
+        println!(
+            "\nThruster signal of {} at each feedback loop:",
+            report::format_phase(max_phase)
+        );
+        print!("{}", report::signal_chart(output, 50));
+    }
+    if let Some(csv) = csv {
+        std::fs::write(csv, report::trace_csv(output))?;
+    }
     Ok(())
 }
@@ -1,78 +1,60 @@
-use std::ops::Deref;
 use std::iter::Iterator;
-use std::collections::HashSet;
-
-#[derive(Debug)]
-pub struct Phase([u8; 5]);
+use std::ops::Deref;
 
-impl Phase {
-    fn new(digits: [u8; 5]) -> Option<Phase> {
-        let mut presence = HashSet::new();
-        for digit in digits.iter() {
-            if presence.contains(digit) {
-                return None
-            } else {
-                presence.insert(digit);
-            }
-        }
-        Some(Phase(digits))
-    }
-}
+/// Phase settings of a chain of amplifiers, one per amplifier
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Phase(Vec<u8>);
 
 impl Deref for Phase {
-    type Target = [u8; 5];
+    type Target = [u8];
 
     fn deref(&self) -> &Self::Target {
         &self.0
     }
 }
 
+/// Iterator over all the permutations of a set of phase settings, in lexicographic order
 pub struct PhaseGenerator {
-    digits: [u8; 5],
+    digits: Vec<u8>,
     ended: bool,
-    high: bool,
 }
 
 impl PhaseGenerator {
-    pub fn new(high: bool) -> PhaseGenerator {
+    pub fn new(mut digits: Vec<u8>) -> PhaseGenerator {
+        digits.sort_unstable();
         PhaseGenerator {
-            digits: if high {[9,8,7,6,5]} else {[4, 3, 2, 1, 0]},
+            digits,
             ended: false,
-            high,
         }
     }
 
-    fn increase(&mut self) {
-        if self.ended {
-            return;
-        }
-        if self.high {
-            if self.digits == [9; 5] {
-                self.ended = true;
-                return;
-            }
-            for i in 0..=4 {
-                self.digits[i] += 1;
-                if self.digits[i] > 9 {
-                    self.digits[i] = 5;
-                } else {
-                    break;
-                }
-            }
-        } else {
-            if self.digits == [4; 5] {
-                self.ended = true;
-                return;
-            }
-            for i in 0..=4 {
-                self.digits[i] += 1;
-                if self.digits[i] > 4 {
-                    self.digits[i] = 0;
-                } else {
-                    break;
-                }
-            }
-        }
+    /// Phases 0 to n-1, used when the amplifiers are in series
+    pub fn series(amplifiers: u8) -> PhaseGenerator {
+        PhaseGenerator::new((0..amplifiers).collect())
+    }
+
+    /// Phases 5 to n+4, used when the amplifiers are in a feedback loop
+    ///
+    /// None when there are too many amplifiers for the last phase to fit in a `u8`.
+    pub fn feedback(amplifiers: u8) -> Option<PhaseGenerator> {
+        let last = 4u8.checked_add(amplifiers)?;
+        Some(PhaseGenerator::new((5..=last).collect()))
+    }
+
+    /// Rearrange the digits into the next permutation, returning false after the last one
+    fn increase(&mut self) -> bool {
+        let digits = &mut self.digits;
+        let pivot = match (1..digits.len()).rev().find(|i| digits[i - 1] < digits[*i]) {
+            Some(i) => i - 1,
+            None => return false,
+        };
+        let successor = (pivot + 1..digits.len())
+            .rev()
+            .find(|i| digits[*i] > digits[pivot])
+            .unwrap();
+        digits.swap(pivot, successor);
+        digits[pivot + 1..].reverse();
+        true
     }
 }
 
@@ -81,18 +63,47 @@ impl Iterator for PhaseGenerator {
 
     fn next(&mut self) -> Option<Self::Item> {
         if self.ended {
-            return None
+            return None;
         }
-        loop {
-            //dbg!(self.digits);
-            if let Some(phase) = Phase::new(self.digits) {
-                self.increase();
-                return Some(phase);
-            }
-            self.increase();
-            if self.ended {
-                return None
+        let phase = Phase(self.digits.clone());
+        self.ended = !self.increase();
+        Some(phase)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn all_permutations() {
+        for n in 0..=7 {
+            let factorial = (1..=n as usize).product::<usize>();
+            for generator in [
+                PhaseGenerator::series(n),
+                PhaseGenerator::feedback(n).unwrap(),
+            ] {
+                let phases = generator.collect::<Vec<_>>();
+                assert_eq!(phases.len(), factorial);
+                assert_eq!(phases.iter().collect::<HashSet<_>>().len(), factorial);
+                assert!(phases.windows(2).all(|w| w[0].0 < w[1].0));
             }
         }
+        let phases = PhaseGenerator::series(3).collect::<Vec<_>>();
+        assert_eq!(*phases[0], [0, 1, 2]);
+        assert_eq!(*phases[5], [2, 1, 0]);
+        assert!(PhaseGenerator::feedback(5)
+            .unwrap()
+            .all(|p| p.iter().all(|d| (5..=9).contains(d))));
+        assert_eq!(
+            PhaseGenerator::feedback(251)
+                .unwrap()
+                .next()
+                .unwrap()
+                .last(),
+            Some(&255)
+        );
+        assert!(PhaseGenerator::feedback(252).is_none());
     }
 }
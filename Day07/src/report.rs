@@ -0,0 +1,123 @@
+use crate::chain::{AmplifierChain, ChainError, ChainMode, ChainOutput};
+use crate::phase::Phase;
+use intcode_computer::Program;
+use std::fmt::Write;
+
+/// Run the chain for every phase setting, best thruster signal first
+pub fn search(
+    program: &Program,
+    mode: ChainMode,
+    amplifiers: u8,
+) -> Result<Vec<(Phase, ChainOutput)>, ChainError> {
+    let mut results = mode
+        .phases(amplifiers)
+        .ok_or(ChainError::TooManyAmplifiers(amplifiers))?
+        .map(|phase| {
+            let output = AmplifierChain::new(program.clone(), &phase, mode).run(0)?;
+            Ok((phase, output))
+        })
+        .collect::<Result<Vec<_>, ChainError>>()?;
+    results.sort_by_key(|(_, output)| std::cmp::Reverse(output.thrusters));
+    Ok(results)
+}
+
+/// Phase settings separated by commas, like `4,1,2,3,0`
+pub fn format_phase(phase: &Phase) -> String {
+    phase
+        .iter()
+        .map(|d| d.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Table of the `top` best phase settings
+pub fn ranking(results: &[(Phase, ChainOutput)], top: usize) -> String {
+    let mut s = String::new();
+    writeln!(s, "rank  phase          loops  thrusters").unwrap();
+    for (rank, (phase, output)) in results.iter().take(top).enumerate() {
+        let loops = output.history.first().map_or(0, |signals| signals.len());
+        writeln!(
+            s,
+            "{:>4}  {:<13}  {:>5}  {}",
+            rank + 1,
+            format_phase(phase),
+            loops,
+            output.thrusters
+        )
+        .unwrap();
+    }
+    s
+}
+
+/// Signal output by every amplifier at every loop, one loop per row
+pub fn trace_csv(output: &ChainOutput) -> String {
+    let mut s = String::from("loop");
+    for i in 0..output.history.len() {
+        write!(s, ",amplifier_{}", i).unwrap();
+    }
+    s.push('\n');
+    let loops = output.history.iter().map(|h| h.len()).max().unwrap_or(0);
+    for l in 0..loops {
+        write!(s, "{}", l + 1).unwrap();
+        for signals in &output.history {
+            match signals.get(l) {
+                Some(signal) => write!(s, ",{}", signal).unwrap(),
+                None => s.push(','),
+            }
+        }
+        s.push('\n');
+    }
+    s
+}
+
+/// Bar chart of the signal leaving the last amplifier at every loop
+///
+/// Signals grow roughly geometrically through the feedback loop, so bars use a
+/// logarithmic scale to keep the first loops visible.
+pub fn signal_chart(output: &ChainOutput, width: usize) -> String {
+    let signals = match output.history.last() {
+        Some(signals) => signals,
+        None => return String::new(),
+    };
+    let scale = |signal: isize| (signal.max(0) as f64 + 1.0).ln();
+    let max = signals.iter().map(|s| scale(*s)).fold(0.0, f64::max);
+    let mut s = String::new();
+    for (l, signal) in signals.iter().enumerate() {
+        let length = if max > 0.0 {
+            (scale(*signal) / max * width as f64).round() as usize
+        } else {
+            0
+        };
+        writeln!(
+            s,
+            "loop {:>3} |{:<width$}| {}",
+            l + 1,
+            "#".repeat(length),
+            signal,
+            width = width
+        )
+        .unwrap();
+    }
+    s
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn trace_and_chart() {
+        let output = ChainOutput {
+            thrusters: 1000,
+            history: vec![vec![1, 10, 100], vec![9, 99, 999], vec![9, 99, 1000]],
+        };
+        assert_eq!(
+            trace_csv(&output),
+            "loop,amplifier_0,amplifier_1,amplifier_2\n1,1,9,9\n2,10,99,99\n3,100,999,1000\n"
+        );
+        let chart = signal_chart(&output, 12);
+        let lines = chart.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], "loop   1 |####        | 9");
+        assert_eq!(lines[2], "loop   3 |############| 1000");
+    }
+}
@@ -1,10 +1,25 @@
+use raster::{Image, RenderOptions};
 use std::io::Read;
 
 const IMAGE_WIDTH: usize = 25;
 const IMAGE_HEIGHT: usize = 6;
 const IMAGE_SIZE: usize = IMAGE_WIDTH * IMAGE_HEIGHT;
 
-fn main() {
+const USAGE: &str = "Usage: space_image_format [--output FILE.(pbm|pgm|svg)]... [--scale N] [--margin N] < input";
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut outputs = Vec::new();
+    let mut options = RenderOptions::new();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--output" => outputs.push(args.next().ok_or(USAGE)?),
+            "--scale" => options = options.with_scale(args.next().ok_or(USAGE)?.parse()?),
+            "--margin" => options = options.with_margin(args.next().ok_or(USAGE)?.parse()?),
+            _ => return Err(USAGE.into()),
+        }
+    }
+
     let mut input = String::new();
     std::io::stdin().read_to_string(&mut input).unwrap();
     let digits = input
@@ -17,34 +32,23 @@ fn main() {
         .iter()
         .map(|layer| layer.iter().filter(|digit| **digit == 0).count())
         .collect::<Vec<_>>();
-    let (layer_with_least_zeros, zeros_count) = layers
+    let (layer_with_least_zeros, _) = layers
         .iter()
         .zip(&layers_zeros_count)
-        .min_by(|layer1, layer2| layer1.1.cmp(&layer2.1))
+        .min_by_key(|(_, zeros_count)| **zeros_count)
         .unwrap();
     let ones_count = layer_with_least_zeros.iter().filter(|d| **d == 1).count();
     let twos_count = layer_with_least_zeros.iter().filter(|d| **d == 2).count();
     println!("{}", ones_count * twos_count);
 
-    let mut final_image = vec![2; IMAGE_SIZE];
-    for layer in layers {
-        for (pos, pixel) in layer.iter().enumerate() {
-            if final_image[pos] == 2 {
-                final_image[pos] = *pixel;
-            }
-        }
+    let image = Image::from_layers(input.trim(), IMAGE_WIDTH, IMAGE_HEIGHT)?;
+    print!("{}", image);
+    match raster::recognize(&image) {
+        Ok(text) => println!("{}", text),
+        Err(error) => eprintln!("{}", error),
     }
-    for line in final_image.chunks(IMAGE_WIDTH) {
-        for d in line {
-            print!(
-                "{}",
-                match d {
-                    0 => ' ',
-                    1 => '\u{2588}',
-                    _ => '?',
-                }
-            );
-        }
-        println!();
+    for output in outputs {
+        image.save(output, &options)?;
     }
+    Ok(())
 }
@@ -10,10 +10,10 @@ fn main() {
     let (pos, visibility) = map.get_asteroid_with_most_visibility().unwrap();
     println!(
         "Asteroid ({},{}) has the most visibility, and can see {} other asteroids.",
-        pos.0, pos.1, visibility
+        pos.x, pos.y, visibility
     );
     let order = map.get_complete_vaporization_by_giant_laser_order(pos);
-    let (x, y) = order[199];
+    let geometry::Vec2 { x, y } = order[199];
     println!("Asteroid ({},{}) will be vaporized in 200th position !", x, y);
     println!("X*100+Y = {}", x * 100 + y);
 }
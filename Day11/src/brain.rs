@@ -0,0 +1,145 @@
+use crate::hull::Color;
+use geometry::Turn;
+use intcode_computer::{
+    Dialect, Instruction, InstructionResult, Interpreter, InterpreterError, Program,
+};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::convert::TryFrom;
+use std::fmt;
+use std::rc::Rc;
+
+#[derive(Debug)]
+pub enum BrainError {
+    Interpreter(InterpreterError),
+    InvalidColor(isize),
+    InvalidTurn(isize),
+}
+
+impl std::error::Error for BrainError {}
+
+impl fmt::Display for BrainError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BrainError::Interpreter(e) => write!(f, "Painting program error: {}", e),
+            BrainError::InvalidColor(c) => write!(f, "Invalid color output: {}", c),
+            BrainError::InvalidTurn(t) => write!(f, "Invalid turn output: {}", t),
+        }
+    }
+}
+
+impl From<InterpreterError> for BrainError {
+    fn from(e: InterpreterError) -> BrainError {
+        BrainError::Interpreter(e)
+    }
+}
+
+/// Decides what the robot paints and where it turns next
+pub trait Brain {
+    /// Color to paint and turn to take given the color under the robot, None once halted
+    fn decide(&mut self, color: Color) -> Result<Option<(Color, Turn)>, BrainError>;
+}
+
+/// Turn ordered by the painting program
+fn turn_from_output(n: isize) -> Result<Turn, isize> {
+    match n {
+        0 => Ok(Turn::Left),
+        1 => Ok(Turn::Right),
+        _ => Err(n),
+    }
+}
+
+/// Brain running the Intcode painting program
+pub struct IntcodeBrain {
+    interpreter: Interpreter,
+    camera: Rc<RefCell<Option<isize>>>,
+    outputs: Rc<RefCell<VecDeque<isize>>>,
+}
+
+impl IntcodeBrain {
+    pub fn new(program: Program) -> IntcodeBrain {
+        let mut interpreter = Interpreter::without_io(program, &Dialect::standard()).unwrap();
+        let camera = Rc::new(RefCell::new(None));
+        let outputs = Rc::new(RefCell::new(VecDeque::new()));
+
+        // Instruction: input, blocking until the robot reads the color under it
+        interpreter.add_instruction(
+            Instruction::new(3, 1, {
+                let camera = camera.clone();
+                move |mut params| match camera.borrow_mut().take() {
+                    Some(color) => {
+                        params[0].write(color)?;
+                        Ok(InstructionResult::Continue)
+                    }
+                    None => Ok(InstructionResult::Block),
+                }
+            })
+            .unwrap(),
+        );
+
+        // Instruction: output
+        interpreter.add_instruction(
+            Instruction::new(4, 1, {
+                let outputs = outputs.clone();
+                move |params| {
+                    outputs.borrow_mut().push_back(params[0].read()?);
+                    Ok(InstructionResult::Continue)
+                }
+            })
+            .unwrap(),
+        );
+
+        IntcodeBrain {
+            interpreter,
+            camera,
+            outputs,
+        }
+    }
+}
+
+impl Brain for IntcodeBrain {
+    fn decide(&mut self, color: Color) -> Result<Option<(Color, Turn)>, BrainError> {
+        *self.camera.borrow_mut() = Some(isize::from(color));
+        // The program stops when it asks for the next color, or when it halts
+        self.interpreter.run()?;
+        let mut outputs = self.outputs.borrow_mut();
+        if outputs.len() < 2 {
+            return Ok(None);
+        }
+        let color = outputs.pop_front().unwrap();
+        let turn = outputs.pop_front().unwrap();
+        let color = Color::try_from(color).map_err(BrainError::InvalidColor)?;
+        let turn = turn_from_output(turn).map_err(BrainError::InvalidTurn)?;
+        Ok(Some((color, turn)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn decide(program: &str) -> Result<Option<(Color, Turn)>, BrainError> {
+        IntcodeBrain::new(program.parse().unwrap()).decide(Color::Black)
+    }
+
+    #[test]
+    fn program_outputs() {
+        assert_eq!(
+            decide("3,9,104,1,104,0,99").unwrap(),
+            Some((Color::White, Turn::Left))
+        );
+        assert_eq!(decide("3,5,99").unwrap(), None);
+        assert!(matches!(
+            decide("3,9,104,2,104,0,99"),
+            Err(BrainError::InvalidColor(2))
+        ));
+        assert!(matches!(
+            decide("3,9,104,0,104,5,99"),
+            Err(BrainError::InvalidTurn(5))
+        ));
+        assert!(matches!(
+            decide("3,9,42"),
+            Err(BrainError::Interpreter(InterpreterError::UnknownOpcode(..)))
+        ));
+    }
+}
@@ -0,0 +1,96 @@
+use geometry::{Bounds, SparseGrid, Vec2};
+use raster::Image;
+use std::convert::TryFrom;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Black,
+    White,
+}
+
+impl TryFrom<isize> for Color {
+    type Error = isize;
+
+    fn try_from(n: isize) -> Result<Color, isize> {
+        match n {
+            0 => Ok(Color::Black),
+            1 => Ok(Color::White),
+            _ => Err(n),
+        }
+    }
+}
+
+impl From<Color> for isize {
+    fn from(color: Color) -> isize {
+        match color {
+            Color::Black => 0,
+            Color::White => 1,
+        }
+    }
+}
+
+/// Panels of the hull, black unless set otherwise, with the number of times each was painted
+#[derive(Debug, Default, Clone)]
+pub struct Hull {
+    panels: SparseGrid<Color>,
+    paint_count: SparseGrid<usize>,
+}
+
+impl Hull {
+    pub fn new() -> Hull {
+        Hull::default()
+    }
+
+    pub fn color(&self, pos: Vec2) -> Color {
+        *self.panels.get(pos).unwrap_or(&Color::Black)
+    }
+
+    /// Set the color of a panel without counting it as painted
+    pub fn set_color(&mut self, pos: Vec2, color: Color) {
+        self.panels.insert(pos, color);
+    }
+
+    pub fn paint(&mut self, pos: Vec2, color: Color) {
+        self.panels.insert(pos, color);
+        let count = self.paint_count.get(pos).map_or(1, |count| count + 1);
+        self.paint_count.insert(pos, count);
+    }
+
+    pub fn panels(&self) -> &SparseGrid<Color> {
+        &self.panels
+    }
+
+    /// Number of panels painted at least once
+    pub fn painted_panels(&self) -> usize {
+        self.paint_count.len()
+    }
+
+    /// Number of panels painted more than once
+    pub fn repainted_panels(&self) -> usize {
+        self.paint_count
+            .iter()
+            .filter(|(_, count)| **count > 1)
+            .count()
+    }
+
+    /// Area covered by the known panels
+    pub fn bounding_box(&self) -> Option<Bounds> {
+        self.panels.bounds()
+    }
+
+    /// Image of the known panels, white ones being on
+    pub fn to_image(&self) -> Image {
+        Image::from_sparse(
+            self.panels
+                .iter()
+                .map(|(pos, color)| (pos.into(), *color == Color::White)),
+        )
+    }
+}
+
+impl fmt::Display for Hull {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_image())
+    }
+}
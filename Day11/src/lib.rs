@@ -0,0 +1,4 @@
+pub mod brain;
+pub mod hull;
+pub mod replay;
+pub mod robot;
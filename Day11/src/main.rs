@@ -1,105 +1,99 @@
-use intcode_computer::{Interpreter, Program};
-use std::cell::RefCell;
-use std::collections::HashMap;
-use std::rc::Rc;
+use intcode_computer::Program;
+use painter::brain::IntcodeBrain;
+use painter::hull::{Color, Hull};
+use painter::replay::{self, Replay};
+use painter::robot::PaintingRobot;
+use raster::RenderOptions;
+use std::io::Write;
+use std::time::Duration;
 
-type Pos = (isize, isize);
+const USAGE: &str =
+    "Usage: painter <program> [--start black|white] [--output FILE.(pbm|pgm|svg)]... \
+[--scale N] [--margin N] [--events FILE.jsonl] [--replay] [--speed STEPS_PER_SECOND]";
 
-fn main() {
-    let file = std::env::args().skip(1).next().expect("No input file");
-    let input = std::fs::read_to_string(file).expect("Could not read input file");
-    let program = input.parse::<Program>().expect("Invalid program");
-    let pos = Rc::new(RefCell::new((0, 0)));
-    let map: Rc<RefCell<HashMap<Pos, bool>>> = Rc::new(RefCell::new(HashMap::new()));
-    map.borrow_mut().insert((0, 0), true);
-    let mut interpreter = Interpreter::complete(
-        program,
-        {
-            let pos = pos.clone();
-            let map = map.clone();
-            move || match map.borrow().get(&pos.borrow()) {
-                Some(color) => {
-                    if *color {
-                        1
-                    } else {
-                        0
-                    }
-                }
-                None => 0,
-            }
-        },
-        {
-            let pos = pos.clone();
-            let map = map.clone();
-            let direction = Rc::new(RefCell::new(0_i8));
-            let first = Rc::new(RefCell::new(true));
-            move |i| {
-                let is_first = *first.borrow();
-                if is_first {
-                    let color = match i {
-                        0 => false,
-                        1 => true,
-                        _ => panic!("Invalid color {}", i)
-                    };
-                    map.borrow_mut().insert(*pos.borrow(), color);
-                    *first.borrow_mut() = false;
-                } else {
-                    match i {
-                        0 => {
-                            let new = (*direction.borrow() + 1) % 4;
-                            *direction.borrow_mut() = new;
-                        }
-                        1 => {
-                            let mut new = *direction.borrow() - 1;
-                            if new < 0 {
-                                new += 4
-                            };
-                            *direction.borrow_mut() = new;
-                        },
-                        _ => panic!("Invalid turn output {}", i)
-                    }
-                    let old_pos = *pos.borrow();
-                    match *direction.borrow() {
-                        0 => *pos.borrow_mut() = (old_pos.0, old_pos.1 - 1),
-
-                        1 => *pos.borrow_mut() = (old_pos.0 - 1, old_pos.1),
-
-                        2 => *pos.borrow_mut() = (old_pos.0, old_pos.1 + 1),
+/// Redraw the hull in the terminal after every step, waiting `delay` between steps
+fn play(mut replay: Replay, delay: Duration) -> std::io::Result<()> {
+    let stdout = std::io::stdout();
+    let mut stdout = stdout.lock();
+    loop {
+        write!(stdout, "\x1b[2J\x1b[H{}", replay.frame())?;
+        writeln!(stdout, "step {}", replay.current_step())?;
+        stdout.flush()?;
+        if replay.step().is_none() {
+            return Ok(());
+        }
+        std::thread::sleep(delay);
+    }
+}
 
-                        3 => *pos.borrow_mut() = (old_pos.0 + 1, old_pos.1),
-                        i => panic!("Invalid direction {}", i)
-                    }
-                    *first.borrow_mut() = true;
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut file = None;
+    let mut outputs = Vec::new();
+    let mut options = RenderOptions::new();
+    let mut start = Color::White;
+    let mut events = None;
+    let mut replay = false;
+    let mut speed = 20.0;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--output" => outputs.push(args.next().ok_or(USAGE)?),
+            "--scale" => options = options.with_scale(args.next().ok_or(USAGE)?.parse()?),
+            "--margin" => options = options.with_margin(args.next().ok_or(USAGE)?.parse()?),
+            "--start" => {
+                start = match args.next().ok_or(USAGE)?.as_str() {
+                    "black" => Color::Black,
+                    "white" => Color::White,
+                    _ => return Err(USAGE.into()),
                 }
             }
-        },
-    );
-    interpreter.execute().unwrap();
-    let painted_panels_number = map.borrow().keys().count();
-    println!("{} panels were painted at least once", painted_panels_number);
-    let min_x = map.borrow().keys().min_by_key(|p1| p1.0).unwrap().0;
-    let min_y = map.borrow().keys().min_by_key(|p1| p1.1).unwrap().1;
-    let mut image: Vec<Vec<bool>> = Vec::new();
-    for (pos, color) in map.borrow().iter() {
-        let pos = ((pos.0 - min_x) as usize, (pos.1 - min_y) as usize);
-        if pos.1 >= image.len() {
-            image.resize(pos.1 + 1, Vec::new());
-        }
-        if pos.0 >= image[pos.1].len() {
-            image[pos.1].resize(pos.0 + 1, false);
+            "--events" => events = Some(args.next().ok_or(USAGE)?),
+            "--replay" => replay = true,
+            "--speed" => speed = args.next().ok_or(USAGE)?.parse::<f64>()?,
+            _ if arg.starts_with("--") || file.is_some() => return Err(USAGE.into()),
+            _ => file = Some(arg),
         }
-        image[pos.1][pos.0] = *color;
     }
-
-    for line in image {
-        for c in line {
-            if c {
-                print!("\u{2588}");
-            } else {
-                print!(" ");
-            }
+    let input = std::fs::read_to_string(file.ok_or(USAGE)?)?;
+    let program = input.parse::<Program>()?;
+    let mut hull = Hull::new();
+    hull.set_color(geometry::Vec2::ZERO, start);
+    let mut robot = PaintingRobot::new(IntcodeBrain::new(program), hull.clone());
+    let steps = robot.run()?;
+    if let Some(events) = events {
+        std::fs::write(events, replay::to_jsonl(robot.events()))?;
+    }
+    if replay {
+        if !speed.is_finite() || speed <= 0.0 {
+            return Err("Replay speed must be a positive number".into());
         }
-        println!();
+        let delay = Duration::try_from_secs_f64(1.0 / speed)?;
+        play(Replay::new(hull, robot.events()), delay)?;
+    }
+    let hull = robot.hull();
+    println!(
+        "{} panels were painted at least once",
+        hull.painted_panels()
+    );
+    println!(
+        "{} panels were painted more than once",
+        hull.repainted_panels()
+    );
+    if let Some(bounds) = hull.bounding_box() {
+        let (min, max) = (bounds.min, bounds.max);
+        println!(
+            "The robot stopped after {} steps, painted area spans ({}, {}) to ({}, {})",
+            steps, min.x, min.y, max.x, max.y
+        );
+    }
+    print!("{}", hull);
+    let image = hull.to_image();
+    match raster::recognize(&image) {
+        Ok(text) => println!("Registration identifier: {}", text),
+        Err(error) => eprintln!("{}", error),
+    }
+    for output in outputs {
+        image.save(output, &options)?;
     }
-    //dbg!(map);
+    Ok(())
 }
@@ -0,0 +1,214 @@
+use crate::hull::{Color, Hull};
+use geometry::{Bounds, Direction, Vec2};
+use std::fmt::Write;
+
+/// Something the robot did during a step, steps being numbered from 1
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    Paint {
+        step: usize,
+        pos: Vec2,
+        color: Color,
+    },
+    Move {
+        step: usize,
+        from: Vec2,
+        to: Vec2,
+        direction: Direction,
+    },
+}
+
+fn color_name(color: Color) -> &'static str {
+    match color {
+        Color::Black => "black",
+        Color::White => "white",
+    }
+}
+
+fn direction_name(direction: Direction) -> &'static str {
+    match direction {
+        Direction::Up => "up",
+        Direction::Left => "left",
+        Direction::Down => "down",
+        Direction::Right => "right",
+    }
+}
+
+impl Event {
+    pub fn step(&self) -> usize {
+        match self {
+            Event::Paint { step, .. } | Event::Move { step, .. } => *step,
+        }
+    }
+
+    /// Event as a single line JSON object
+    pub fn to_json(&self) -> String {
+        match self {
+            Event::Paint { step, pos, color } => format!(
+                r#"{{"step":{},"event":"paint","x":{},"y":{},"color":"{}"}}"#,
+                step,
+                pos.x,
+                pos.y,
+                color_name(*color)
+            ),
+            Event::Move {
+                step,
+                from,
+                to,
+                direction,
+            } => format!(
+                r#"{{"step":{},"event":"move","from":[{},{}],"to":[{},{}],"direction":"{}"}}"#,
+                step,
+                from.x,
+                from.y,
+                to.x,
+                to.y,
+                direction_name(*direction)
+            ),
+        }
+    }
+}
+
+/// Event log in the JSON lines format, one event per line
+pub fn to_jsonl(events: &[Event]) -> String {
+    events.iter().fold(String::new(), |mut s, event| {
+        writeln!(s, "{}", event.to_json()).unwrap();
+        s
+    })
+}
+
+/// Replays recorded events step by step over the hull the robot started on
+pub struct Replay<'a> {
+    events: &'a [Event],
+    next: usize,
+    hull: Hull,
+    position: Vec2,
+    direction: Direction,
+    step: usize,
+    bounds: Bounds,
+}
+
+impl<'a> Replay<'a> {
+    /// Replay of a robot starting at (0, 0) facing up
+    pub fn new(hull: Hull, events: &'a [Event]) -> Replay<'a> {
+        let positions = events
+            .iter()
+            .flat_map(|event| match event {
+                Event::Paint { pos, .. } => vec![*pos],
+                Event::Move { from, to, .. } => vec![*from, *to],
+            })
+            .chain(hull.panels().iter().map(|(pos, _)| pos))
+            .chain(std::iter::once(Vec2::ZERO))
+            .collect::<Vec<_>>();
+        Replay {
+            events,
+            next: 0,
+            hull,
+            position: Vec2::ZERO,
+            direction: Direction::Up,
+            step: 0,
+            bounds: Bounds::from_points(positions).unwrap(),
+        }
+    }
+
+    /// Apply the events of the next step, returns its number or None at the end of the log
+    pub fn step(&mut self) -> Option<usize> {
+        let step = self.events.get(self.next)?.step();
+        while let Some(event) = self.events.get(self.next) {
+            if event.step() != step {
+                break;
+            }
+            match *event {
+                Event::Paint { pos, color, .. } => self.hull.paint(pos, color),
+                Event::Move { to, direction, .. } => {
+                    self.position = to;
+                    self.direction = direction;
+                }
+            }
+            self.next += 1;
+        }
+        self.step = step;
+        Some(step)
+    }
+
+    /// Number of the last replayed step, 0 before the first one
+    pub fn current_step(&self) -> usize {
+        self.step
+    }
+
+    pub fn hull(&self) -> &Hull {
+        &self.hull
+    }
+
+    /// Hull over the area covered by the whole replay, with the robot drawn as an arrow
+    pub fn frame(&self) -> String {
+        let mut s = String::new();
+        for pos in self.bounds.positions() {
+            if pos == self.position {
+                s.push(match self.direction {
+                    Direction::Up => '^',
+                    Direction::Left => '<',
+                    Direction::Down => 'v',
+                    Direction::Right => '>',
+                });
+            } else {
+                s.push(match self.hull.color(pos) {
+                    Color::White => '\u{2588}',
+                    Color::Black => ' ',
+                });
+            }
+            if pos.x == self.bounds.max.x {
+                s.push('\n');
+            }
+        }
+        s
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn replay() {
+        let events = [
+            Event::Paint {
+                step: 1,
+                pos: Vec2::ZERO,
+                color: Color::White,
+            },
+            Event::Move {
+                step: 1,
+                from: Vec2::ZERO,
+                to: Vec2::new(-1, 0),
+                direction: Direction::Left,
+            },
+            Event::Paint {
+                step: 2,
+                pos: Vec2::new(-1, 0),
+                color: Color::White,
+            },
+            Event::Move {
+                step: 2,
+                from: Vec2::new(-1, 0),
+                to: Vec2::new(-1, 1),
+                direction: Direction::Down,
+            },
+        ];
+        assert_eq!(
+            to_jsonl(&events[..2]),
+            "{\"step\":1,\"event\":\"paint\",\"x\":0,\"y\":0,\"color\":\"white\"}\n\
+             {\"step\":1,\"event\":\"move\",\"from\":[0,0],\"to\":[-1,0],\"direction\":\"left\"}\n"
+        );
+
+        let mut replay = Replay::new(Hull::new(), &events);
+        assert_eq!(replay.frame(), " ^\n  \n");
+        assert_eq!(replay.step(), Some(1));
+        assert_eq!(replay.frame(), "<\u{2588}\n  \n");
+        assert_eq!(replay.step(), Some(2));
+        assert_eq!(replay.frame(), "\u{2588}\u{2588}\nv \n");
+        assert_eq!(replay.step(), None);
+        assert_eq!(replay.current_step(), 2);
+        assert_eq!(replay.hull().painted_panels(), 2);
+    }
+}
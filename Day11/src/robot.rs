@@ -0,0 +1,140 @@
+use crate::brain::{Brain, BrainError};
+use crate::hull::Hull;
+use crate::replay::Event;
+use geometry::{Direction, Vec2};
+
+/// Robot painting the hull under the orders of its brain
+pub struct PaintingRobot<B: Brain> {
+    brain: B,
+    hull: Hull,
+    position: Vec2,
+    direction: Direction,
+    steps: usize,
+    events: Vec<Event>,
+}
+
+impl<B: Brain> PaintingRobot<B> {
+    /// Robot starting at (0, 0) facing up
+    pub fn new(brain: B, hull: Hull) -> PaintingRobot<B> {
+        PaintingRobot {
+            brain,
+            hull,
+            position: Vec2::ZERO,
+            direction: Direction::Up,
+            steps: 0,
+            events: Vec::new(),
+        }
+    }
+
+    /// Paint the current panel then move, returns false once the brain has halted
+    pub fn step(&mut self) -> Result<bool, BrainError> {
+        let color = self.hull.color(self.position);
+        Ok(match self.brain.decide(color)? {
+            Some((color, turn)) => {
+                let step = self.steps + 1;
+                let from = self.position;
+                self.hull.paint(from, color);
+                self.direction = self.direction.turn(turn);
+                self.position = self.direction.forward(from);
+                self.steps = step;
+                self.events.push(Event::Paint {
+                    step,
+                    pos: from,
+                    color,
+                });
+                self.events.push(Event::Move {
+                    step,
+                    from,
+                    to: self.position,
+                    direction: self.direction,
+                });
+                true
+            }
+            None => false,
+        })
+    }
+
+    /// Step until the brain halts, returns the number of steps
+    pub fn run(&mut self) -> Result<usize, BrainError> {
+        while self.step()? {}
+        Ok(self.steps)
+    }
+
+    pub fn hull(&self) -> &Hull {
+        &self.hull
+    }
+
+    pub fn position(&self) -> Vec2 {
+        self.position
+    }
+
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Paint and move events of every step so far
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::hull::Color;
+    use geometry::Turn;
+
+    struct ScriptedBrain(std::vec::IntoIter<(Color, Turn)>);
+
+    impl Brain for ScriptedBrain {
+        fn decide(&mut self, _: Color) -> Result<Option<(Color, Turn)>, BrainError> {
+            Ok(self.0.next())
+        }
+    }
+
+    #[test]
+    fn scripted_robot() {
+        use Color::*;
+        let script = vec![
+            (White, Turn::Left),
+            (Black, Turn::Left),
+            (White, Turn::Left),
+            (White, Turn::Left),
+            (Black, Turn::Right),
+            (White, Turn::Left),
+            (White, Turn::Left),
+        ];
+        let mut robot = PaintingRobot::new(ScriptedBrain(script.into_iter()), Hull::new());
+        assert!(robot.step().unwrap());
+        assert_eq!(robot.position(), Vec2::new(-1, 0));
+        assert_eq!(robot.direction(), Direction::Left);
+
+        assert_eq!(robot.run().unwrap(), 7);
+        assert_eq!(robot.position(), Vec2::new(0, -1));
+        assert_eq!(robot.direction(), Direction::Left);
+        let hull = robot.hull();
+        assert_eq!(hull.painted_panels(), 6);
+        assert_eq!(hull.repainted_panels(), 1);
+        let bounds = hull.bounding_box().unwrap();
+        assert_eq!(
+            (bounds.min, bounds.max),
+            (Vec2::new(-1, -1), Vec2::new(1, 1))
+        );
+        assert_eq!(hull.color(Vec2::ZERO), Black);
+        assert_eq!(hull.color(Vec2::new(1, 0)), White);
+        assert_eq!(robot.events().len(), 14);
+        assert_eq!(
+            robot.events()[13],
+            Event::Move {
+                step: 7,
+                from: Vec2::new(1, -1),
+                to: Vec2::new(0, -1),
+                direction: Direction::Left,
+            }
+        );
+    }
+}
@@ -1,11 +1,10 @@
-use crate::position::Position;
-use std::cmp::Ordering;
+use geometry::Vec3;
 use std::fmt;
 
 #[derive(Clone, Copy)]
 struct Object {
-    position: Position,
-    velocity: Position,
+    position: Vec3,
+    velocity: Vec3,
 }
 
 pub struct GravitySystem {
@@ -13,64 +12,24 @@ pub struct GravitySystem {
 }
 
 fn update_velocity(mut o1: Object, mut o2: Object) -> (Object, Object) {
-    let mut v1 = &mut o1.velocity;
-    let mut v2 = &mut o2.velocity;
-    let p1 = o1.position;
-    let p2 = o2.position;
-
-    match p1.x.cmp(&p2.x) {
-        Ordering::Less => {
-            v1.x += 1;
-            v2.x -= 1;
-        }
-        Ordering::Greater => {
-            v1.x -= 1;
-            v2.x += 1;
-        }
-        Ordering::Equal => {}
-    }
-    match p1.y.cmp(&p2.y) {
-        Ordering::Less => {
-            v1.y += 1;
-            v2.y -= 1;
-        }
-        Ordering::Greater => {
-            v1.y -= 1;
-            v2.y += 1;
-        }
-        Ordering::Equal => {}
-    }
-    match p1.z.cmp(&p2.z) {
-        Ordering::Less => {
-            v1.z += 1;
-            v2.z -= 1;
-        }
-        Ordering::Greater => {
-            v1.z -= 1;
-            v2.z += 1;
-        }
-        Ordering::Equal => {}
-    }
+    // Each axis pulls the objects one step closer
+    let pull = (o2.position - o1.position).signum();
+    o1.velocity += pull;
+    o2.velocity -= pull;
     (o1, o2)
 }
 
 impl Object {
     fn update_position(&mut self) {
-        self.position.x += self.velocity.x;
-        self.position.y += self.velocity.y;
-        self.position.z += self.velocity.z;
+        self.position += self.velocity;
     }
 
     fn potential_energy(&self) -> usize {
-        self.position.x.abs() as usize
-            + self.position.y.abs() as usize
-            + self.position.z.abs() as usize
+        self.position.manhattan()
     }
 
     fn cynetic_energy(&self) -> usize {
-        self.velocity.x.abs() as usize
-            + self.velocity.y.abs() as usize
-            + self.velocity.z.abs() as usize
+        self.velocity.manhattan()
     }
 
     fn total_energy(&self) -> usize {
@@ -79,13 +38,13 @@ impl Object {
 }
 
 impl GravitySystem {
-    pub fn new(positions: Vec<Position>) -> GravitySystem {
+    pub fn new(positions: Vec<Vec3>) -> GravitySystem {
         GravitySystem {
             objects: positions
                 .iter()
                 .map(|p| Object {
                     position: *p,
-                    velocity: Position::default(),
+                    velocity: Vec3::ZERO,
                 })
                 .collect::<Vec<_>>(),
         }
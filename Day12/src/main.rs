@@ -2,14 +2,13 @@ mod gravity_system;
 mod position;
 
 use gravity_system::GravitySystem;
-use position::Position;
 use std::io::BufRead;
 
 fn main() {
     let pos = std::io::stdin()
         .lock()
         .lines()
-        .map(|l| l.unwrap().parse::<Position>().unwrap())
+        .map(|l| position::parse_position(&l.unwrap()).unwrap())
         .collect::<Vec<_>>();
     let mut system = GravitySystem::new(pos);
     // for s in 0..=100 {
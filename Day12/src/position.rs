@@ -1,27 +1,7 @@
+use geometry::Vec3;
 use lazy_static::lazy_static;
 use regex::Regex;
 use snafu::Snafu;
-use std::fmt;
-use std::str::FromStr;
-
-#[derive(Debug, PartialEq, Clone, Copy)]
-pub struct Position {
-    pub x: isize,
-    pub y: isize,
-    pub z: isize,
-}
-
-impl Default for Position {
-    fn default() -> Position {
-        Position { x: 0, y: 0, z: 0 }
-    }
-}
-
-impl fmt::Display for Position {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "<x={}, y={}, z={}>", self.x, self.y, self.z)
-    }
-}
 
 #[derive(Debug, Snafu)]
 pub enum PositionParseError {
@@ -29,24 +9,21 @@ pub enum PositionParseError {
     InvalidSyntax { s: String },
 }
 
-impl FromStr for Position {
-    type Err = PositionParseError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        lazy_static! {
-            static ref RE: Regex =
-                Regex::new(r"<x=(?P<x>-?\d+), y=(?P<y>-?\d+), z=(?P<z>-?\d+)>").unwrap();
-        }
-        let cap = match RE.captures(s) {
-            Some(v) => v,
-            None => return Err(PositionParseError::InvalidSyntax { s: s.to_owned() }),
-        };
-        Ok(Position {
-            x: cap.name("x").unwrap().as_str().parse::<isize>().unwrap(),
-            y: cap.name("y").unwrap().as_str().parse::<isize>().unwrap(),
-            z: cap.name("z").unwrap().as_str().parse::<isize>().unwrap(),
-        })
+/// Position written as `<x=.., y=.., z=..>`
+pub fn parse_position(s: &str) -> Result<Vec3, PositionParseError> {
+    lazy_static! {
+        static ref RE: Regex =
+            Regex::new(r"<x=(?P<x>-?\d+), y=(?P<y>-?\d+), z=(?P<z>-?\d+)>").unwrap();
     }
+    let cap = match RE.captures(s) {
+        Some(v) => v,
+        None => return Err(PositionParseError::InvalidSyntax { s: s.to_owned() }),
+    };
+    Ok(Vec3 {
+        x: cap.name("x").unwrap().as_str().parse::<isize>().unwrap(),
+        y: cap.name("y").unwrap().as_str().parse::<isize>().unwrap(),
+        z: cap.name("z").unwrap().as_str().parse::<isize>().unwrap(),
+    })
 }
 
 #[cfg(test)]
@@ -55,7 +32,7 @@ mod test {
 
     #[test]
     fn to_string() {
-        let pos = Position {
+        let pos = Vec3 {
             x: -7,
             y: 17,
             z: -11,
@@ -65,15 +42,15 @@ mod test {
 
     #[test]
     fn from_string() {
-        let pos = "<x=-7, y=17, z=-11>".parse::<Position>().unwrap();
+        let pos = parse_position("<x=-7, y=17, z=-11>").unwrap();
         assert_eq!(
             pos,
-            Position {
+            Vec3 {
                 x: -7,
                 y: 17,
                 z: -11,
             }
         );
-        assert!("lol".parse::<Position>().is_err());
+        assert!(parse_position("lol").is_err());
     }
 }
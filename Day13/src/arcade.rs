@@ -1,11 +1,19 @@
-use intcode_computer::{Interpreter, Program};
+use crate::protocol::{ArcadeEvent, Decoder, ProtocolError};
+use crate::stats::GameStats;
+use crate::strategy::JoystickStrategy;
+use geometry::{Grid, Vec2};
+use intcode_computer::{
+    Dialect, Instruction, InstructionResult, Interpreter, InterpreterError, MachineState, Program,
+    StopReason,
+};
 use std::cell::{Ref, RefCell};
+use std::collections::VecDeque;
+use std::convert::TryFrom;
+use std::fmt;
+use std::io;
 use std::rc::Rc;
-use std::cmp::Ordering;
 
-type Pos = (isize, isize);
-
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TileType {
     Empty,
     Wall,
@@ -14,24 +22,41 @@ pub enum TileType {
     Ball,
 }
 
-type TileMap = Vec<Vec<TileType>>;
+pub type TileMap = Grid<TileType>;
+
+impl TryFrom<isize> for TileType {
+    type Error = isize;
 
-impl From<isize> for TileType {
-    fn from(n: isize) -> TileType {
+    fn try_from(n: isize) -> Result<TileType, isize> {
         match n {
-            0 => TileType::Empty,
-            1 => TileType::Wall,
-            2 => TileType::Block,
-            3 => TileType::Paddle,
-            4 => TileType::Ball,
-            _ => TileType::Empty,
+            0 => Ok(TileType::Empty),
+            1 => Ok(TileType::Wall),
+            2 => Ok(TileType::Block),
+            3 => Ok(TileType::Paddle),
+            4 => Ok(TileType::Ball),
+            _ => Err(n),
         }
     }
 }
 
-impl Into<char> for TileType {
-    fn into(self) -> char {
-        match self {
+impl TryFrom<char> for TileType {
+    type Error = char;
+
+    fn try_from(c: char) -> Result<TileType, char> {
+        match c {
+            ' ' => Ok(TileType::Empty),
+            '#' => Ok(TileType::Wall),
+            'X' => Ok(TileType::Block),
+            '-' => Ok(TileType::Paddle),
+            'o' => Ok(TileType::Ball),
+            _ => Err(c),
+        }
+    }
+}
+
+impl From<TileType> for char {
+    fn from(tile: TileType) -> char {
+        match tile {
             TileType::Empty => ' ',
             TileType::Wall => '#',
             TileType::Block => 'X',
@@ -41,101 +66,364 @@ impl Into<char> for TileType {
     }
 }
 
-#[derive(Clone, Copy)]
-enum JoystickPosition {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JoystickPosition {
     Left = -1,
     Neutral = 0,
     Right = 1,
 }
 
-struct ArcadeState {
-    tiles: TileMap,
-    score: isize,
-    joystick: JoystickPosition,
-    ball_pos: Pos,
-    paddle_pos: Pos
+/// Screen and score of the game, with the inputs given so far
+#[derive(Debug, Clone)]
+pub struct ArcadeState {
+    pub(crate) tiles: TileMap,
+    pub(crate) score: isize,
+    pub(crate) joystick: JoystickPosition,
+    pub(crate) ball_pos: Vec2,
+    pub(crate) paddle_pos: Vec2,
+    pub(crate) inputs: usize,
+    pub(crate) moves: usize,
+    pub(crate) frames: usize,
+}
+
+impl Default for ArcadeState {
+    fn default() -> ArcadeState {
+        ArcadeState {
+            tiles: Grid::new(0, 0, TileType::Empty),
+            score: 0,
+            joystick: JoystickPosition::Neutral,
+            ball_pos: Vec2::ZERO,
+            paddle_pos: Vec2::ZERO,
+            inputs: 0,
+            moves: 0,
+            frames: 0,
+        }
+    }
+}
+
+impl ArcadeState {
+    pub fn tiles(&self) -> &TileMap {
+        &self.tiles
+    }
+
+    pub fn score(&self) -> isize {
+        self.score
+    }
+
+    /// Last joystick position given to the game
+    pub fn joystick(&self) -> JoystickPosition {
+        self.joystick
+    }
+
+    pub fn ball_pos(&self) -> Vec2 {
+        self.ball_pos
+    }
+
+    pub fn paddle_pos(&self) -> Vec2 {
+        self.paddle_pos
+    }
+
+    /// Number of times the game read the joystick
+    pub fn inputs(&self) -> usize {
+        self.inputs
+    }
+
+    /// Number of inputs where the joystick was not neutral
+    pub fn moves(&self) -> usize {
+        self.moves
+    }
+
+    /// Number of frames drawn, a frame ending every time the game reads the joystick or halts
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+
+    /// Width of the screen, walls included
+    pub fn width(&self) -> usize {
+        self.tiles.width()
+    }
+
+    pub fn block_count(&self) -> usize {
+        self.tiles
+            .iter()
+            .filter(|(_, t)| **t == TileType::Block)
+            .count()
+    }
+
+    pub(crate) fn draw(&mut self, pos: Vec2, tile_type: TileType) {
+        self.tiles
+            .expand(pos.x as usize + 1, pos.y as usize + 1, TileType::Empty);
+        self.tiles[pos] = tile_type;
+        if tile_type == TileType::Ball {
+            self.ball_pos = pos;
+        }
+        if tile_type == TileType::Paddle {
+            self.paddle_pos = pos;
+        }
+    }
+
+    pub(crate) fn apply(&mut self, event: ArcadeEvent) {
+        match event {
+            ArcadeEvent::DrawTile { pos, tile } => self.draw(pos, tile),
+            ArcadeEvent::ScoreUpdate(score) => self.score = score,
+        }
+    }
+
+    fn input(&mut self, joystick: JoystickPosition) {
+        self.joystick = joystick;
+        self.inputs += 1;
+        if joystick != JoystickPosition::Neutral {
+            self.moves += 1;
+        }
+    }
+}
+
+impl fmt::Display for ArcadeState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.tiles)
+    }
+}
+
+/// Whole arcade between two frames: Intcode machine and game state
+///
+/// The game outputs whole tiles before reading the joystick, so no tile is
+/// half drawn when a frame ends.
+#[derive(Debug, Clone)]
+pub struct ArcadeSnapshot {
+    pub machine: MachineState,
+    pub state: ArcadeState,
+    pub halted: bool,
+}
+
+#[derive(Debug)]
+pub enum ArcadeError {
+    Protocol(ProtocolError),
+    Interpreter(InterpreterError),
+    Strategy(io::Error),
+}
+
+impl std::error::Error for ArcadeError {}
+
+impl fmt::Display for ArcadeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArcadeError::Protocol(e) => write!(f, "{}", e),
+            ArcadeError::Interpreter(e) => write!(f, "Arcade program error: {}", e),
+            ArcadeError::Strategy(e) => write!(f, "Could not play the joystick: {}", e),
+        }
+    }
+}
+
+impl From<ProtocolError> for ArcadeError {
+    fn from(e: ProtocolError) -> ArcadeError {
+        ArcadeError::Protocol(e)
+    }
+}
+
+impl From<InterpreterError> for ArcadeError {
+    fn from(e: InterpreterError) -> ArcadeError {
+        ArcadeError::Interpreter(e)
+    }
 }
 
 pub struct Arcade {
     interpreter: Interpreter,
-    state: Rc<RefCell< ArcadeState>>,
+    state: Rc<RefCell<ArcadeState>>,
+    strategy: Box<dyn JoystickStrategy>,
+    joystick: Rc<RefCell<Option<JoystickPosition>>>,
+    decoder: Rc<RefCell<Decoder>>,
+    error: Rc<RefCell<Option<ProtocolError>>>,
+    stats: Rc<RefCell<GameStats>>,
+    halted: bool,
+    quit: bool,
+    history: VecDeque<ArcadeSnapshot>,
+    history_size: usize,
 }
 
 impl Arcade {
-    pub fn new(program: Program) -> Arcade {
-        let state = Rc::new(RefCell::new(ArcadeState {
-            tiles: Vec::new(),
-            score: 0,
-            joystick: JoystickPosition::Neutral,
-            ball_pos: (0, 0),
-            paddle_pos: (0, 0)
-        }));
-        Arcade {
-            interpreter: Interpreter::complete(
-                program,
-                // Game input: joystick
-                {
-                    let state = state.clone();
-                    move || {
-                        let state = state.borrow();
-                        let joystick = match state.paddle_pos.0.cmp(&state.ball_pos.0) {
-                            Ordering::Less => JoystickPosition::Right,
-                            Ordering::Greater => JoystickPosition::Left,
-                            Ordering::Equal => JoystickPosition::Neutral
-                        };
-                        joystick as isize
+    /// Arcade whose joystick is moved by `strategy`
+    pub fn new(program: Program, strategy: Box<dyn JoystickStrategy>) -> Arcade {
+        let state = Rc::new(RefCell::new(ArcadeState::default()));
+        let joystick = Rc::new(RefCell::new(None));
+        let decoder = Rc::new(RefCell::new(Decoder::new()));
+        let error = Rc::new(RefCell::new(None));
+        let stats = Rc::new(RefCell::new(GameStats::new()));
+        let mut interpreter = Interpreter::without_io(program, &Dialect::standard()).unwrap();
+
+        // Game output: decoded into events updating the state, stopping the game on invalid output
+        interpreter.add_instruction(
+            Instruction::new(4, 1, {
+                let state = state.clone();
+                let decoder = decoder.clone();
+                let error = error.clone();
+                let stats = stats.clone();
+                move |params| match decoder.borrow_mut().push(params[0].read()?) {
+                    Ok(event) => {
+                        if let Some(event) = event {
+                            let mut state = state.borrow_mut();
+                            let frame = state.frames + 1;
+                            stats.borrow_mut().record(frame, &event, &state);
+                            state.apply(event);
+                        }
+                        Ok(InstructionResult::Continue)
                     }
-                },
-                // Game output: print on the tiles map
-                {
-                    let mut input_state = Box::new(0);
-                    let mut pos: Box<Pos> = Box::new((0, 0));
-                    let state = state.clone();
-                    move |output| {
+                    Err(e) => {
+                        *error.borrow_mut() = Some(e);
+                        Ok(InstructionResult::Quit)
+                    }
+                }
+            })
+            .unwrap(),
+        );
+
+        // Game input: joystick, blocking at the end of every frame until the strategy has played
+        interpreter.add_instruction(
+            Instruction::new(3, 1, {
+                let state = state.clone();
+                let stats = stats.clone();
+                let joystick = joystick.clone();
+                move |mut params| match joystick.borrow_mut().take() {
+                    Some(position) => {
                         let mut state = state.borrow_mut();
-                        match *input_state {
-                            0 => pos.0 = output,
-                            1 => pos.1 = output,
-                            2 => {
-                                if *pos == (-1, 0) {
-                                    state.score = output;
-                                } else {
-                                    let len_y = state.tiles.len();
-                                    let pos_y = pos.1 as usize;
-                                    if pos_y >= len_y {
-                                        state.tiles.resize(pos_y + 1, Vec::new());
-                                    }
-                                    let len_x = state.tiles[pos_y].len();
-                                    let pos_x = pos.0 as usize;
-                                    if pos_x >= len_x {
-                                        state.tiles[pos_y]
-                                            .resize(pos_x + 1, TileType::Empty);
-                                    }
-                                    let tile_type = TileType::from(output);
-                                    state.tiles[pos_y][pos_x] = tile_type;
-                                    if tile_type == TileType::Ball {
-                                        state.ball_pos = *pos;
-                                    }
-                                    if tile_type == TileType::Paddle {
-                                        state.paddle_pos = *pos;
-                                    }
-                                }
-                            }
-                            _ => panic!("Invalid arcarde output state: {}", *input_state),
-                        }
-                        *input_state = (*input_state + 1) % 3;
+                        stats.borrow_mut().record_input(state.frames + 1, position);
+                        state.input(position);
+                        params[0].write(position as isize)?;
+                        Ok(InstructionResult::Continue)
                     }
-                },
-            ),
-            state
+                    None => Ok(InstructionResult::Block),
+                }
+            })
+            .unwrap(),
+        );
+
+        Arcade {
+            interpreter,
+            state,
+            strategy,
+            joystick,
+            decoder,
+            error,
+            stats,
+            halted: false,
+            quit: false,
+            history: VecDeque::new(),
+            history_size: 0,
+        }
+    }
+
+    /// Arcade resuming a game from a snapshot
+    pub fn from_snapshot(snapshot: ArcadeSnapshot, strategy: Box<dyn JoystickStrategy>) -> Arcade {
+        let mut arcade = Arcade::new(snapshot.machine.memory.clone(), strategy);
+        arcade.restore(snapshot);
+        arcade
+    }
+
+    /// Keep a snapshot of the last `frames` frames for instant rewind
+    pub fn set_history_size(&mut self, frames: usize) {
+        self.history_size = frames;
+        while self.history.len() > frames {
+            self.history.pop_front();
         }
     }
 
-    pub fn execute(&mut self) {
-        self.interpreter.execute().expect("Arcade intcode error")
+    pub fn snapshot(&self) -> ArcadeSnapshot {
+        ArcadeSnapshot {
+            machine: self.interpreter.save_state(),
+            state: self.state.borrow().clone(),
+            halted: self.halted,
+        }
+    }
+
+    /// Go back to a snapshot, the frame history being kept
+    pub fn restore(&mut self, snapshot: ArcadeSnapshot) {
+        self.stats.borrow_mut().rewind_to(snapshot.state.frames);
+        self.interpreter.restore_state(snapshot.machine);
+        *self.state.borrow_mut() = snapshot.state;
+        *self.joystick.borrow_mut() = None;
+        *self.decoder.borrow_mut() = Decoder::new();
+        *self.error.borrow_mut() = None;
+        self.halted = snapshot.halted;
+        self.quit = false;
+    }
+
+    /// Go back up to `frames` frames in the history, returns how many frames were undone
+    pub fn rewind(&mut self, frames: usize) -> usize {
+        let frames = frames.min(self.history.len().saturating_sub(1));
+        self.history.truncate(self.history.len() - frames);
+        if frames > 0 {
+            let snapshot = self.history.back().unwrap().clone();
+            self.restore(snapshot);
+        }
+        frames
+    }
+
+    /// Run the game until it has drawn the next frame and waits for the joystick,
+    /// returns false once the game is over
+    ///
+    /// Invalid output or instructions from the game and strategy failures end it with an error.
+    pub fn next_frame(&mut self) -> Result<bool, ArcadeError> {
+        if self.halted || self.quit {
+            return Ok(false);
+        }
+        if self.state.borrow().frames > 0 {
+            let position = self
+                .strategy
+                .joystick(&self.state.borrow())
+                .map_err(ArcadeError::Strategy)?;
+            if self.strategy.wants_to_quit() {
+                self.quit = true;
+                return Ok(false);
+            }
+            if let Some(frames) = self.strategy.wants_to_rewind() {
+                self.rewind(frames);
+                return Ok(true);
+            }
+            *self.joystick.borrow_mut() = Some(position);
+        }
+        let reason = self.interpreter.run()?;
+        self.halted = reason == StopReason::Halted;
+        if let Some(error) = self.error.borrow_mut().take() {
+            return Err(error.into());
+        }
+        if self.halted {
+            self.decoder.borrow_mut().finish()?;
+        }
+        self.state.borrow_mut().frames += 1;
+        if self.history_size > 0 {
+            if self.history.len() == self.history_size {
+                self.history.pop_front();
+            }
+            self.history.push_back(self.snapshot());
+        }
+        Ok(!self.halted)
     }
 
-    pub fn tiles(&self) -> Ref<TileMap> {
+    /// Play until the game is over
+    pub fn execute(&mut self) -> Result<(), ArcadeError> {
+        while self.next_frame()? {}
+        Ok(())
+    }
+
+    /// Whether the game halted
+    pub fn is_over(&self) -> bool {
+        self.halted
+    }
+
+    /// Whether the strategy stopped the game before it was over
+    pub fn has_quit(&self) -> bool {
+        self.quit
+    }
+
+    pub fn state(&self) -> Ref<'_, ArcadeState> {
+        self.state.borrow()
+    }
+
+    /// Event log of the game, since the start or since it was loaded
+    pub fn stats(&self) -> Ref<'_, GameStats> {
+        self.stats.borrow()
+    }
+
+    pub fn tiles(&self) -> Ref<'_, TileMap> {
         let r = self.state.borrow();
         Ref::map(r, |s| &s.tiles)
     }
@@ -145,12 +433,119 @@ impl Arcade {
     }
 
     pub fn display_map(&self) {
-        for line in &self.state.borrow().tiles {
-            for tile in line {
-                let c: char = (*tile).into();
-                print!("{}", c);
-            }
-            println!();
+        print!("{}", self.state.borrow());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::strategy::GreedyStrategy;
+
+    fn game() -> Arcade {
+        let mut program = include_str!("../input").parse::<Program>().unwrap();
+        program.write(0, 2).unwrap();
+        Arcade::new(program, Box::new(GreedyStrategy))
+    }
+
+    #[test]
+    fn rewind() {
+        let mut arcade = game();
+        arcade.set_history_size(10);
+        for _ in 0..100 {
+            assert!(arcade.next_frame().unwrap());
+        }
+        let snapshot = arcade.snapshot();
+        for _ in 0..5 {
+            arcade.next_frame().unwrap();
+        }
+        assert_eq!(arcade.state().frames(), 105);
+        assert_eq!(arcade.rewind(5), 5);
+        assert_eq!(arcade.state().frames(), 100);
+        assert_eq!(arcade.state().to_save(), snapshot.state.to_save());
+        // Only the last 10 frames are kept, 96 to 105
+        assert_eq!(arcade.rewind(50), 4);
+        assert_eq!(arcade.state().frames(), 96);
+
+        // Replaying from the past gives the same frames again
+        for _ in 0..4 {
+            arcade.next_frame().unwrap();
         }
+        assert_eq!(arcade.state().to_save(), snapshot.state.to_save());
+        let saved = ArcadeSnapshot::from_save(&snapshot.to_save()).unwrap();
+        let mut resumed = Arcade::from_snapshot(saved, Box::new(GreedyStrategy));
+        resumed.execute().unwrap();
+        arcade.execute().unwrap();
+        assert!(resumed.is_over());
+        assert!(ArcadeSnapshot::from_save(&resumed.snapshot().to_save()).is_ok());
+        assert_eq!(resumed.score(), arcade.score());
+
+        let stats = arcade.stats();
+        assert_eq!(stats.inputs(), arcade.state().inputs());
+        let points = stats
+            .score_increments()
+            .iter()
+            .map(|(_, p)| p)
+            .sum::<isize>();
+        assert_eq!(points, arcade.score());
+        assert_eq!(
+            stats.blocks_destroyed().last().map(|(_, total)| *total),
+            Some(stats.score_increments().len())
+        );
+    }
+
+    #[test]
+    fn invalid_output() {
+        // Draws a wall at (1, 2), then outputs 7 as a tile id
+        let program = "104,1,104,2,104,1,104,0,104,0,104,7,99"
+            .parse::<Program>()
+            .unwrap();
+        let mut arcade = Arcade::new(program, Box::new(GreedyStrategy));
+        assert!(matches!(
+            arcade.execute(),
+            Err(ArcadeError::Protocol(ProtocolError::InvalidTileId {
+                id: 7,
+                ..
+            }))
+        ));
+        assert!(arcade.is_over());
+        assert_eq!(arcade.tiles()[Vec2::new(1, 2)], TileType::Wall);
+
+        let program = "104,1,104,2,104,1,104,0,99".parse::<Program>().unwrap();
+        let mut arcade = Arcade::new(program, Box::new(GreedyStrategy));
+        assert!(matches!(
+            arcade.execute(),
+            Err(ArcadeError::Protocol(ProtocolError::TruncatedTriple(values))) if values == [0]
+        ));
+
+        let program = "104,1,104,2,104,1,42".parse::<Program>().unwrap();
+        let mut arcade = Arcade::new(program, Box::new(GreedyStrategy));
+        assert!(matches!(
+            arcade.execute(),
+            Err(ArcadeError::Interpreter(InterpreterError::UnknownOpcode(
+                42,
+                _
+            )))
+        ));
+    }
+
+    struct BrokenStrategy;
+
+    impl JoystickStrategy for BrokenStrategy {
+        fn joystick(&mut self, _: &ArcadeState) -> io::Result<JoystickPosition> {
+            Err(io::Error::new(io::ErrorKind::UnexpectedEof, "no player"))
+        }
+    }
+
+    #[test]
+    fn strategy_failure() {
+        let mut program = include_str!("../input").parse::<Program>().unwrap();
+        program.write(0, 2).unwrap();
+        let mut arcade = Arcade::new(program, Box::new(BrokenStrategy));
+        assert!(arcade.next_frame().unwrap());
+        assert!(matches!(
+            arcade.next_frame(),
+            Err(ArcadeError::Strategy(e)) if e.kind() == io::ErrorKind::UnexpectedEof
+        ));
     }
 }
@@ -0,0 +1,8 @@
+pub mod arcade;
+pub mod protocol;
+pub mod save;
+pub mod screen;
+pub mod stats;
+pub mod strategy;
+#[cfg(unix)]
+pub mod terminal;
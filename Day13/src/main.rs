@@ -1,25 +1,199 @@
 use intcode_computer::Program;
 
-mod arcade;
+use arcade::arcade::{Arcade, ArcadeSnapshot};
+use arcade::screen::{self, FrameRecorder, LiveScreen, Screen};
+use arcade::strategy::*;
+use std::io::BufWriter;
+use std::time::Duration;
 
-use arcade::{Arcade, TileType};
+const USAGE: &str =
+    "Usage: arcade [program] [--strategy greedy|predict|keyboard|human|script:FILE] [--compare] \
+[--live] [--delay MS] [--record FILE] [--save FILE] [--load FILE] [--history FRAMES] [--stats FILE.csv]
+       arcade --playback FILE [--delay MS]";
 
-fn main() {
-    let mut program = Program::from_stdin().unwrap();
+/// Frame time when playing with the keyboard
+const HUMAN_FRAME_TIME: Duration = Duration::from_millis(150);
+
+/// Frames undone by each rewind when playing with the keyboard
+const HUMAN_REWIND_FRAMES: usize = 20;
+
+fn strategy(name: &str) -> Result<Box<dyn JoystickStrategy>, Box<dyn std::error::Error>> {
+    Ok(match name {
+        #[cfg(unix)]
+        "human" => Box::new(HumanStrategy::new(HUMAN_FRAME_TIME, HUMAN_REWIND_FRAMES)?),
+        "greedy" => Box::new(GreedyStrategy),
+        "predict" => Box::new(PredictionStrategy::default()),
+        "keyboard" => Box::new(KeyboardStrategy::new(
+            std::io::BufReader::new(std::io::stdin()),
+            std::io::stdout(),
+        )),
+        _ => match name.strip_prefix("script:") {
+            Some(file) => Box::new(std::fs::read_to_string(file)?.parse::<ScriptedStrategy>()?),
+            None => return Err(USAGE.into()),
+        },
+    })
+}
+
+/// Arcade with free play enabled
+fn new_game(program: &Program, strategy: Box<dyn JoystickStrategy>) -> Arcade {
+    let mut program = program.clone();
     program.write(0, 2).unwrap();
-    let mut arcade = Arcade::new(program);
-    arcade.execute();
-    let block_count = arcade
-        .tiles()
-        .iter()
-        .map(|l| l.iter())
-        .flatten()
-        .filter(|t| **t == TileType::Block)
-        .count();
+    Arcade::new(program, strategy)
+}
+
+fn play(
+    mut arcade: Arcade,
+    screen: Option<Box<dyn Screen>>,
+) -> Result<Arcade, Box<dyn std::error::Error>> {
+    match screen {
+        Some(mut screen) => loop {
+            let running = arcade.next_frame()?;
+            screen.show(&arcade.state())?;
+            if !running {
+                break;
+            }
+        },
+        None => arcade.execute()?,
+    }
+    Ok(arcade)
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut file = None;
+    let mut strategies = Vec::new();
+    let mut compare = false;
+    let mut live = false;
+    let mut delay = Duration::from_millis(30);
+    let mut record = None;
+    let mut playback = None;
+    let mut save = "arcade.save".to_string();
+    let mut load = None;
+    let mut history = None;
+    let mut stats = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--strategy" => strategies.push(args.next().ok_or(USAGE)?),
+            "--compare" => compare = true,
+            "--live" => live = true,
+            "--delay" => delay = Duration::from_millis(args.next().ok_or(USAGE)?.parse()?),
+            "--record" => record = Some(args.next().ok_or(USAGE)?),
+            "--playback" => playback = Some(args.next().ok_or(USAGE)?),
+            "--save" => save = args.next().ok_or(USAGE)?,
+            "--load" => load = Some(args.next().ok_or(USAGE)?),
+            "--stats" => stats = Some(args.next().ok_or(USAGE)?),
+            "--history" => history = Some(args.next().ok_or(USAGE)?.parse::<usize>()?),
+            _ if arg.starts_with("--") || file.is_some() => return Err(USAGE.into()),
+            _ => file = Some(arg),
+        }
+    }
+    if let Some(playback) = playback {
+        let recording = std::io::BufReader::new(std::fs::File::open(playback)?);
+        screen::play_recording(recording, std::io::stdout(), delay)?;
+        return Ok(());
+    }
+    // Frames are either drawn in the terminal or recorded, not both
+    if live && record.is_some() {
+        return Err(USAGE.into());
+    }
+    if strategies.is_empty() {
+        strategies.push("greedy".to_string());
+        if compare {
+            strategies.push("predict".to_string());
+        }
+    }
+    let keyboard = strategies.iter().any(|s| s == "keyboard" || s == "human");
+    let program = match (file, &load) {
+        (Some(file), _) => Some(std::fs::read_to_string(file)?.parse::<Program>()?),
+        // A save holds the whole memory of the game
+        (None, Some(_)) => None,
+        (None, None) if keyboard => {
+            return Err("Playing with the keyboard needs the program in a file".into())
+        }
+        (None, None) => Some(Program::from_stdin()?),
+    };
+
+    if compare {
+        let program = program.ok_or(USAGE)?;
+        println!("strategy          score  blocks left  inputs  moves  paddle hits  last block");
+        for name in &strategies {
+            let arcade = play(new_game(&program, strategy(name)?), None)?;
+            let state = arcade.state();
+            let stats = arcade.stats();
+            println!(
+                "{:<16}  {:>5}  {:>11}  {:>6}  {:>5}  {:>11}  {:>10}",
+                name,
+                state.score(),
+                state.block_count(),
+                state.inputs(),
+                state.moves(),
+                stats.paddle_hits(),
+                stats
+                    .last_block_frame()
+                    .map_or("-".to_string(), |f| f.to_string())
+            );
+        }
+        return Ok(());
+    }
+
+    let human = strategies[0] == "human";
+    let mut arcade = match (load, program) {
+        (Some(load), _) => {
+            let snapshot = ArcadeSnapshot::from_save(&std::fs::read_to_string(load)?)?;
+            Arcade::from_snapshot(snapshot, strategy(&strategies[0])?)
+        }
+        (None, Some(program)) => new_game(&program, strategy(&strategies[0])?),
+        (None, None) => unreachable!(),
+    };
+    arcade.set_history_size(history.unwrap_or(if human { 200 } else { 0 }));
+    let screen: Option<Box<dyn Screen>> = match record {
+        Some(record) => Some(Box::new(FrameRecorder::new(BufWriter::new(
+            std::fs::File::create(record)?,
+        )))),
+        // The human strategy paces the game itself
+        None if human => Some(Box::new(LiveScreen::new(
+            std::io::stdout(),
+            Duration::from_millis(0),
+        ))),
+        None if live => Some(Box::new(LiveScreen::new(std::io::stdout(), delay))),
+        None => None,
+    };
+    let show_map = screen.is_none();
+    let arcade = play(arcade, screen)?;
+    if arcade.has_quit() {
+        std::fs::write(&save, arcade.snapshot().to_save())?;
+        println!("\nGame saved to {}", save);
+    }
+    if let Some(stats) = stats {
+        std::fs::write(stats, arcade.stats().to_csv())?;
+    }
+    let state = arcade.state();
+    if human {
+        let outcome = if arcade.has_quit() {
+            "Quit"
+        } else if state.block_count() == 0 {
+            "You won!"
+        } else {
+            "Game over"
+        };
+        println!("{}", outcome);
+        println!("Score: {}", state.score());
+        println!("Blocks left: {}", state.block_count());
+        println!("Frames played: {}", state.frames());
+        println!(
+            "Joystick moves: {} out of {} inputs",
+            state.moves(),
+            state.inputs()
+        );
+        return Ok(());
+    }
     println!(
         "Total number of blocks at the end of the game: {}",
-        block_count
+        state.block_count()
     );
-    println!("Final score: {}", arcade.score());
-    arcade.display_map();
+    println!("Final score: {}", state.score());
+    if show_map {
+        print!("{}", state);
+    }
+    Ok(())
 }
@@ -0,0 +1,147 @@
+use crate::arcade::TileType;
+use geometry::Vec2;
+use std::convert::TryFrom;
+use std::fmt;
+
+/// Position of a triple that updates the score instead of drawing a tile
+const SCORE_POS: Vec2 = Vec2::new(-1, 0);
+
+/// What a complete output triple of the game means
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArcadeEvent {
+    DrawTile { pos: Vec2, tile: TileType },
+    ScoreUpdate(isize),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProtocolError {
+    InvalidTileId {
+        pos: Vec2,
+        id: isize,
+    },
+    NegativeCoordinates(Vec2),
+    /// Values left over when the game halted
+    TruncatedTriple(Vec<isize>),
+}
+
+impl std::error::Error for ProtocolError {}
+
+impl fmt::Display for ProtocolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProtocolError::InvalidTileId { pos, id } => {
+                write!(f, "Invalid tile id {} at {}", id, pos)
+            }
+            ProtocolError::NegativeCoordinates(pos) => {
+                write!(f, "Tile drawn at negative coordinates {}", pos)
+            }
+            ProtocolError::TruncatedTriple(values) => write!(
+                f,
+                "Game halted in the middle of an output triple: {:?}",
+                values
+            ),
+        }
+    }
+}
+
+/// Turns the outputs of the game, read one value at a time, into events
+#[derive(Debug, Clone, Default)]
+pub struct Decoder {
+    pending: Vec<isize>,
+}
+
+impl Decoder {
+    pub fn new() -> Decoder {
+        Decoder::default()
+    }
+
+    /// Read the next output, returns an event once a triple is complete
+    pub fn push(&mut self, value: isize) -> Result<Option<ArcadeEvent>, ProtocolError> {
+        self.pending.push(value);
+        if self.pending.len() < 3 {
+            return Ok(None);
+        }
+        let (x, y, value) = (self.pending[0], self.pending[1], self.pending[2]);
+        self.pending.clear();
+        let pos = Vec2::new(x, y);
+        if pos == SCORE_POS {
+            return Ok(Some(ArcadeEvent::ScoreUpdate(value)));
+        }
+        if x < 0 || y < 0 {
+            return Err(ProtocolError::NegativeCoordinates(pos));
+        }
+        let tile =
+            TileType::try_from(value).map_err(|id| ProtocolError::InvalidTileId { pos, id })?;
+        Ok(Some(ArcadeEvent::DrawTile { pos, tile }))
+    }
+
+    /// Check that no triple was left incomplete, once the game has halted
+    pub fn finish(&mut self) -> Result<(), ProtocolError> {
+        if self.pending.is_empty() {
+            Ok(())
+        } else {
+            Err(ProtocolError::TruncatedTriple(std::mem::take(
+                &mut self.pending,
+            )))
+        }
+    }
+
+    /// Decode every output of a game that has halted
+    pub fn decode_all(outputs: &[isize]) -> Result<Vec<ArcadeEvent>, ProtocolError> {
+        let mut decoder = Decoder::new();
+        let mut events = Vec::new();
+        for output in outputs {
+            events.extend(decoder.push(*output)?);
+        }
+        decoder.finish()?;
+        Ok(events)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn events() {
+        assert_eq!(
+            Decoder::decode_all(&[1, 2, 3, 6, 5, 4, -1, 0, 12345]),
+            Ok(vec![
+                ArcadeEvent::DrawTile {
+                    pos: Vec2::new(1, 2),
+                    tile: TileType::Paddle
+                },
+                ArcadeEvent::DrawTile {
+                    pos: Vec2::new(6, 5),
+                    tile: TileType::Ball
+                },
+                ArcadeEvent::ScoreUpdate(12345),
+            ])
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            Decoder::decode_all(&[1, 2, 5]),
+            Err(ProtocolError::InvalidTileId {
+                pos: Vec2::new(1, 2),
+                id: 5
+            })
+        );
+        assert_eq!(
+            Decoder::decode_all(&[-1, 1, 0]),
+            Err(ProtocolError::NegativeCoordinates(Vec2::new(-1, 1)))
+        );
+        assert_eq!(
+            Decoder::decode_all(&[0, 0, 1, 3, 4]),
+            Err(ProtocolError::TruncatedTriple(vec![3, 4]))
+        );
+        let mut decoder = Decoder::new();
+        assert_eq!(decoder.push(2), Ok(None));
+        assert_eq!(decoder.push(3), Ok(None));
+        assert!(decoder.push(9).is_err());
+        // The decoder starts over with the next triple
+        assert_eq!(decoder.finish(), Ok(()));
+    }
+}
@@ -0,0 +1,342 @@
+use crate::arcade::{ArcadeSnapshot, ArcadeState, JoystickPosition, TileType};
+use geometry::Vec2;
+use intcode_computer::{MachineState, Program};
+use std::convert::TryFrom;
+use std::fmt::{self, Write};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SaveError {
+    MissingField(&'static str),
+    InvalidLine(usize, String),
+    InvalidTile(usize, char),
+    /// Values that could not come from a saved game
+    Inconsistent(&'static str),
+}
+
+impl std::error::Error for SaveError {}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveError::MissingField(field) => write!(f, "Missing {} in save", field),
+            SaveError::InvalidLine(line, content) => {
+                write!(f, "Invalid save line {}: {:?}", line, content)
+            }
+            SaveError::InvalidTile(line, c) => {
+                write!(f, "Invalid tile {:?} on save line {}", c, line)
+            }
+            SaveError::Inconsistent(reason) => write!(f, "Inconsistent save: {}", reason),
+        }
+    }
+}
+
+impl SaveError {
+    /// Same error for a save starting `lines` lines further in a file
+    fn shift(self, lines: usize) -> SaveError {
+        match self {
+            SaveError::InvalidLine(line, content) => SaveError::InvalidLine(line + lines, content),
+            SaveError::InvalidTile(line, c) => SaveError::InvalidTile(line + lines, c),
+            e => e,
+        }
+    }
+}
+
+/// First line of an arcade save file
+const SAVE_HEADER: &str = "arcade save";
+
+/// Value of a `name value` line
+fn field<'a>(
+    lines: &mut impl Iterator<Item = (usize, &'a str)>,
+    name: &'static str,
+) -> Result<(usize, &'a str), SaveError> {
+    let (n, line) = lines.next().ok_or(SaveError::MissingField(name))?;
+    match line.split_once(' ') {
+        Some((field, value)) if field == name => Ok((n, value)),
+        _ => Err(SaveError::InvalidLine(n + 1, line.to_string())),
+    }
+}
+
+impl ArcadeSnapshot {
+    /// Save file of the whole arcade, Intcode machine then game state
+    pub fn to_save(&self) -> String {
+        let mut s = String::new();
+        writeln!(s, "{}", SAVE_HEADER).unwrap();
+        writeln!(
+            s,
+            "instruction_pointer {}",
+            self.machine.instruction_pointer
+        )
+        .unwrap();
+        writeln!(s, "relative_offset {}", self.machine.relative_offset).unwrap();
+        writeln!(s, "steps {}", self.machine.steps).unwrap();
+        writeln!(s, "halted {}", self.halted).unwrap();
+        writeln!(s, "memory {}", self.machine.memory).unwrap();
+        s.push_str(&self.state.to_save());
+        s
+    }
+
+    pub fn from_save(save: &str) -> Result<ArcadeSnapshot, SaveError> {
+        let mut lines = save.lines().enumerate();
+        match lines.next() {
+            Some((_, SAVE_HEADER)) => (),
+            Some((_, line)) => return Err(SaveError::InvalidLine(1, line.to_string())),
+            None => return Err(SaveError::MissingField("header")),
+        }
+        let invalid = |(n, value): (usize, &str)| SaveError::InvalidLine(n + 1, value.to_string());
+        let line = field(&mut lines, "instruction_pointer")?;
+        let instruction_pointer = line.1.parse().map_err(|_| invalid(line))?;
+        let line = field(&mut lines, "relative_offset")?;
+        let relative_offset = line.1.parse().map_err(|_| invalid(line))?;
+        let line = field(&mut lines, "steps")?;
+        let steps = line.1.parse().map_err(|_| invalid(line))?;
+        let line = field(&mut lines, "halted")?;
+        let halted = line.1.parse().map_err(|_| invalid(line))?;
+        let line = field(&mut lines, "memory")?;
+        let memory = line.1.parse::<Program>().map_err(|_| invalid(line))?;
+        let header_lines = line.0 + 1;
+        let rest = save
+            .lines()
+            .skip(header_lines)
+            .collect::<Vec<_>>()
+            .join("\n");
+        let (state, _) = ArcadeState::from_save(&rest).map_err(|e| e.shift(header_lines))?;
+        // Games are saved between frames, while the machine waits for the joystick or has halted
+        if instruction_pointer >= memory.len() {
+            return Err(SaveError::Inconsistent(
+                "instruction pointer outside memory",
+            ));
+        }
+        match (halted, memory[instruction_pointer] % 100) {
+            (true, 99) | (false, 3) => (),
+            (true, _) => {
+                return Err(SaveError::Inconsistent(
+                    "halted away from a stop instruction",
+                ))
+            }
+            (false, _) => return Err(SaveError::Inconsistent("not waiting for the joystick")),
+        }
+        Ok(ArcadeSnapshot {
+            machine: MachineState {
+                memory,
+                instruction_pointer,
+                relative_offset,
+                steps,
+            },
+            state,
+            halted,
+        })
+    }
+}
+
+impl ArcadeState {
+    /// Text save of the state: one `name values` line per field, then the tiles
+    pub fn to_save(&self) -> String {
+        let mut s = String::new();
+        writeln!(s, "score {}", self.score).unwrap();
+        writeln!(s, "ball {} {}", self.ball_pos.x, self.ball_pos.y).unwrap();
+        writeln!(s, "paddle {} {}", self.paddle_pos.x, self.paddle_pos.y).unwrap();
+        writeln!(s, "joystick {}", self.joystick as isize).unwrap();
+        writeln!(s, "inputs {}", self.inputs).unwrap();
+        writeln!(s, "moves {}", self.moves).unwrap();
+        writeln!(s, "frames {}", self.frames).unwrap();
+        writeln!(s, "tiles {}", self.tiles.height()).unwrap();
+        write!(s, "{}", self).unwrap();
+        s
+    }
+
+    /// State read back from `to_save`, returns the number of lines used with it
+    pub fn from_save(save: &str) -> Result<(ArcadeState, usize), SaveError> {
+        let mut state = ArcadeState::default();
+        let mut lines = save.lines().enumerate();
+        let invalid = |n: usize, line: &str| SaveError::InvalidLine(n + 1, line.to_string());
+        let mut fields = 0;
+        while fields < 8 {
+            let (n, line) = lines.next().ok_or(SaveError::MissingField("tiles"))?;
+            let mut words = line.split_whitespace();
+            let name = words.next().ok_or_else(|| invalid(n, line))?;
+            let values = words
+                .map(|w| w.parse::<isize>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| invalid(n, line))?;
+            match (name, values.as_slice()) {
+                ("score", [score]) => state.score = *score,
+                ("ball", [x, y]) => state.ball_pos = Vec2::new(*x, *y),
+                ("paddle", [x, y]) => state.paddle_pos = Vec2::new(*x, *y),
+                ("joystick", [-1]) => state.joystick = JoystickPosition::Left,
+                ("joystick", [0]) => state.joystick = JoystickPosition::Neutral,
+                ("joystick", [1]) => state.joystick = JoystickPosition::Right,
+                ("inputs", [n]) if *n >= 0 => state.inputs = *n as usize,
+                ("moves", [n]) if *n >= 0 => state.moves = *n as usize,
+                ("frames", [n]) if *n >= 0 => state.frames = *n as usize,
+                ("tiles", [rows]) if *rows >= 0 => {
+                    for y in 0..*rows {
+                        let (n, line) = lines.next().ok_or(SaveError::MissingField("tiles"))?;
+                        let row = line
+                            .chars()
+                            .map(|c| {
+                                TileType::try_from(c).map_err(|c| SaveError::InvalidTile(n + 1, c))
+                            })
+                            .collect::<Result<Vec<_>, _>>()?;
+                        state
+                            .tiles
+                            .expand(row.len(), y as usize + 1, TileType::Empty);
+                        for (x, tile) in row.into_iter().enumerate() {
+                            state.tiles[Vec2::new(x as isize, y)] = tile;
+                        }
+                    }
+                    state.validate()?;
+                    return Ok((state, n + 1 + *rows as usize));
+                }
+                _ => return Err(invalid(n, line)),
+            }
+            fields += 1;
+        }
+        Err(SaveError::MissingField("tiles"))
+    }
+
+    /// Check that the fields of a loaded state agree with each other and with the screen
+    fn validate(&self) -> Result<(), SaveError> {
+        if self.score < 0 {
+            return Err(SaveError::Inconsistent("negative score"));
+        }
+        if self.moves > self.inputs || self.inputs > self.frames {
+            return Err(SaveError::Inconsistent("more moves than inputs or frames"));
+        }
+        for (tile, pos, reason) in [
+            (TileType::Ball, self.ball_pos, "ball not on the screen"),
+            (
+                TileType::Paddle,
+                self.paddle_pos,
+                "paddle not on the screen",
+            ),
+        ] {
+            let drawn = self.tiles.iter().any(|(_, t)| *t == tile);
+            if drawn && self.tiles.get(pos) != Some(&tile) {
+                return Err(SaveError::Inconsistent(reason));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn state_round_trip() {
+        let mut state = ArcadeState::default();
+        state.draw(Vec2::ZERO, TileType::Wall);
+        state.draw(Vec2::new(2, 0), TileType::Wall);
+        state.draw(Vec2::new(1, 1), TileType::Block);
+        state.draw(Vec2::new(0, 2), TileType::Ball);
+        state.draw(Vec2::new(1, 3), TileType::Paddle);
+        state.score = 42;
+        state.joystick = JoystickPosition::Left;
+        state.inputs = 3;
+        state.moves = 2;
+        state.frames = 4;
+        let save = state.to_save();
+        assert!(save.starts_with("score 42\nball 0 2\npaddle 1 3\njoystick -1\n"));
+        let (loaded, lines) = ArcadeState::from_save(&save).unwrap();
+        assert_eq!(lines, save.lines().count());
+        assert_eq!(loaded.to_save(), save);
+        assert_eq!(loaded.block_count(), 1);
+
+        assert_eq!(
+            ArcadeState::from_save("score 1\nball x 2\n").unwrap_err(),
+            SaveError::InvalidLine(2, "ball x 2".to_string())
+        );
+        assert_eq!(
+            ArcadeState::from_save(&save.replace("X", "?")).unwrap_err(),
+            SaveError::InvalidTile(10, '?')
+        );
+        assert_eq!(
+            ArcadeState::from_save("score 1\n").unwrap_err(),
+            SaveError::MissingField("tiles")
+        );
+    }
+
+    #[test]
+    fn snapshot_round_trip() {
+        let mut state = ArcadeState::default();
+        state.draw(Vec2::new(1, 1), TileType::Block);
+        let snapshot = ArcadeSnapshot {
+            machine: MachineState {
+                memory: "1,2,3,-4".parse().unwrap(),
+                instruction_pointer: 2,
+                relative_offset: -7,
+                steps: 12,
+            },
+            state,
+            halted: false,
+        };
+        let save = snapshot.to_save();
+        assert!(save.starts_with(
+            "arcade save\ninstruction_pointer 2\nrelative_offset -7\nsteps 12\nhalted false\nmemory 1,2,3,-4\nscore 0\n"
+        ));
+        let loaded = ArcadeSnapshot::from_save(&save).unwrap();
+        assert_eq!(loaded.to_save(), save);
+
+        assert_eq!(
+            ArcadeSnapshot::from_save(&save.replace("steps 12", "steps")).unwrap_err(),
+            SaveError::InvalidLine(4, "steps".to_string())
+        );
+        assert_eq!(
+            ArcadeSnapshot::from_save(&save.replace("score 0", "score zero")).unwrap_err(),
+            SaveError::InvalidLine(7, "score zero".to_string())
+        );
+    }
+
+    #[test]
+    fn corrupt_saves() {
+        let mut state = ArcadeState::default();
+        state.draw(Vec2::new(1, 1), TileType::Ball);
+        state.draw(Vec2::new(1, 2), TileType::Paddle);
+        state.frames = 1;
+        let snapshot = ArcadeSnapshot {
+            machine: MachineState {
+                memory: "3,5,99,0,0,0".parse().unwrap(),
+                instruction_pointer: 0,
+                relative_offset: 0,
+                steps: 0,
+            },
+            state,
+            halted: false,
+        };
+        let save = snapshot.to_save();
+        assert!(ArcadeSnapshot::from_save(&save).is_ok());
+
+        let truncated = save.lines().take(14).collect::<Vec<_>>().join("\n");
+        assert_eq!(
+            ArcadeSnapshot::from_save(&truncated).unwrap_err(),
+            SaveError::MissingField("tiles")
+        );
+        for (from, to, reason) in [
+            (
+                "instruction_pointer 0",
+                "instruction_pointer 6",
+                "instruction pointer outside memory",
+            ),
+            (
+                "instruction_pointer 0",
+                "instruction_pointer 1",
+                "not waiting for the joystick",
+            ),
+            (
+                "halted false",
+                "halted true",
+                "halted away from a stop instruction",
+            ),
+            ("score 0", "score -3", "negative score"),
+            ("moves 0", "moves 1", "more moves than inputs or frames"),
+            ("ball 1 1", "ball 2 1", "ball not on the screen"),
+            ("paddle 1 2", "paddle 0 0", "paddle not on the screen"),
+        ] {
+            assert_eq!(
+                ArcadeSnapshot::from_save(&save.replace(from, to)).unwrap_err(),
+                SaveError::Inconsistent(reason)
+            );
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use crate::arcade::ArcadeState;
+use std::io::{self, BufRead, Write};
+use std::time::Duration;
+
+/// Separates the frames of a recording
+const FRAME_SEPARATOR: &str = "\u{c}";
+
+/// Status line followed by the tiles
+pub fn frame(state: &ArcadeState) -> String {
+    format!(
+        "Score: {}  Blocks left: {}  Frame: {}\n{}",
+        state.score(),
+        state.block_count(),
+        state.frames(),
+        state
+    )
+}
+
+/// Shows the game after every frame
+pub trait Screen {
+    fn show(&mut self, state: &ArcadeState) -> io::Result<()>;
+}
+
+/// Redraws the terminal in place, waiting `delay` after each frame
+pub struct LiveScreen<W: Write> {
+    output: W,
+    delay: Duration,
+}
+
+impl<W: Write> LiveScreen<W> {
+    pub fn new(output: W, delay: Duration) -> LiveScreen<W> {
+        LiveScreen { output, delay }
+    }
+
+    /// Draw a frame already rendered with `frame`
+    fn draw(&mut self, frame: &str) -> io::Result<()> {
+        // Cursor home then clear the screen
+        write!(self.output, "\x1b[H\x1b[2J{}", frame)?;
+        self.output.flush()?;
+        std::thread::sleep(self.delay);
+        Ok(())
+    }
+}
+
+impl<W: Write> Screen for LiveScreen<W> {
+    fn show(&mut self, state: &ArcadeState) -> io::Result<()> {
+        self.draw(&frame(state))
+    }
+}
+
+/// Writes every frame to a recording without showing anything
+pub struct FrameRecorder<W: Write> {
+    output: W,
+}
+
+impl<W: Write> FrameRecorder<W> {
+    pub fn new(output: W) -> FrameRecorder<W> {
+        FrameRecorder { output }
+    }
+}
+
+impl<W: Write> Screen for FrameRecorder<W> {
+    fn show(&mut self, state: &ArcadeState) -> io::Result<()> {
+        writeln!(self.output, "{}{}", frame(state), FRAME_SEPARATOR)
+    }
+}
+
+/// Frames of a recording made by `FrameRecorder`
+pub fn read_recording<R: BufRead>(input: R) -> io::Result<Vec<String>> {
+    let mut frames = Vec::new();
+    let mut frame = String::new();
+    for line in input.lines() {
+        let line = line?;
+        if line == FRAME_SEPARATOR {
+            frames.push(std::mem::take(&mut frame));
+        } else {
+            frame.push_str(&line);
+            frame.push('\n');
+        }
+    }
+    if !frame.is_empty() {
+        frames.push(frame);
+    }
+    Ok(frames)
+}
+
+/// Show recorded frames in the terminal
+pub fn play_recording<R: BufRead, W: Write>(
+    input: R,
+    output: W,
+    delay: Duration,
+) -> io::Result<()> {
+    let mut screen = LiveScreen::new(output, delay);
+    for frame in read_recording(input)? {
+        screen.draw(&frame)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::arcade::TileType;
+    use geometry::Vec2;
+
+    #[test]
+    fn record_and_play() {
+        let mut state = ArcadeState::default();
+        state.draw(Vec2::ZERO, TileType::Wall);
+        state.draw(Vec2::new(1, 0), TileType::Block);
+        state.draw(Vec2::new(2, 1), TileType::Ball);
+        let mut recording = Vec::new();
+        let mut recorder = FrameRecorder::new(&mut recording);
+        recorder.show(&state).unwrap();
+        state.draw(Vec2::new(1, 0), TileType::Empty);
+        recorder.show(&state).unwrap();
+
+        let frames = read_recording(&recording[..]).unwrap();
+        assert_eq!(
+            frames,
+            vec![
+                "Score: 0  Blocks left: 1  Frame: 0\n#X \n  o\n",
+                "Score: 0  Blocks left: 0  Frame: 0\n#  \n  o\n",
+            ]
+        );
+
+        let mut output = Vec::new();
+        play_recording(&recording[..], &mut output, Duration::from_millis(0)).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.matches("\x1b[H\x1b[2J").count(), 2);
+        assert!(output.ends_with("#  \n  o\n"));
+    }
+}
@@ -0,0 +1,252 @@
+use crate::arcade::{ArcadeState, JoystickPosition, TileType};
+use crate::protocol::ArcadeEvent;
+use geometry::Vec2;
+use std::fmt::Write;
+
+/// Something that happened during a game, in the frame it happened, frames being numbered from 1
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameEvent {
+    JoystickInput {
+        frame: usize,
+        joystick: JoystickPosition,
+    },
+    /// The ball bounced back up from right above the paddle at `pos`
+    PaddleHit {
+        frame: usize,
+        pos: Vec2,
+    },
+    BlockDestroyed {
+        frame: usize,
+        pos: Vec2,
+    },
+    /// Score gained, tied to the block destroyed just before in the same frame
+    ScoreIncrement {
+        frame: usize,
+        points: isize,
+        block: Option<Vec2>,
+    },
+}
+
+impl GameEvent {
+    pub fn frame(&self) -> usize {
+        match self {
+            GameEvent::JoystickInput { frame, .. }
+            | GameEvent::PaddleHit { frame, .. }
+            | GameEvent::BlockDestroyed { frame, .. }
+            | GameEvent::ScoreIncrement { frame, .. } => *frame,
+        }
+    }
+}
+
+/// Event log of a game, with the statistics drawn from it
+#[derive(Debug, Clone, Default)]
+pub struct GameStats {
+    events: Vec<GameEvent>,
+    /// Last two positions of the ball, most recent last
+    ball: [Option<Vec2>; 2],
+    /// Destroyed block not tied to a score increment yet
+    unscored_block: Option<(usize, Vec2)>,
+    last_block_frame: Option<usize>,
+}
+
+impl GameStats {
+    pub fn new() -> GameStats {
+        GameStats::default()
+    }
+
+    /// Record what an event from the game means, before it is applied to `state`
+    pub fn record(&mut self, frame: usize, event: &ArcadeEvent, state: &ArcadeState) {
+        match *event {
+            ArcadeEvent::DrawTile { pos, tile } => {
+                let previous = state.tiles().get(pos);
+                if previous == Some(&TileType::Block) && tile != TileType::Block {
+                    self.events.push(GameEvent::BlockDestroyed { frame, pos });
+                    self.unscored_block = Some((frame, pos));
+                    if state.block_count() == 1 {
+                        self.last_block_frame = Some(frame);
+                    }
+                }
+                if tile == TileType::Ball {
+                    if let [Some(before), Some(last)] = self.ball {
+                        let paddle_row = state.paddle_pos().y - 1;
+                        if last.y > before.y && pos.y < last.y && last.y == paddle_row {
+                            self.events.push(GameEvent::PaddleHit { frame, pos: last });
+                        }
+                    }
+                    self.ball = [self.ball[1], Some(pos)];
+                }
+            }
+            ArcadeEvent::ScoreUpdate(score) => {
+                let points = score - state.score();
+                if points != 0 {
+                    let block = match self.unscored_block.take() {
+                        Some((block_frame, pos)) if block_frame == frame => Some(pos),
+                        _ => None,
+                    };
+                    self.events.push(GameEvent::ScoreIncrement {
+                        frame,
+                        points,
+                        block,
+                    });
+                }
+            }
+        }
+    }
+
+    pub fn record_input(&mut self, frame: usize, joystick: JoystickPosition) {
+        self.events
+            .push(GameEvent::JoystickInput { frame, joystick });
+    }
+
+    /// Forget what happened after `frame`, when the game goes back in time
+    pub fn rewind_to(&mut self, frame: usize) {
+        self.events.retain(|event| event.frame() <= frame);
+        self.ball = [None, None];
+        self.unscored_block = None;
+        if self.last_block_frame.is_some_and(|f| f > frame) {
+            self.last_block_frame = None;
+        }
+    }
+
+    pub fn events(&self) -> &[GameEvent] {
+        &self.events
+    }
+
+    pub fn paddle_hits(&self) -> usize {
+        self.events
+            .iter()
+            .filter(|e| matches!(e, GameEvent::PaddleHit { .. }))
+            .count()
+    }
+
+    pub fn inputs(&self) -> usize {
+        self.events
+            .iter()
+            .filter(|e| matches!(e, GameEvent::JoystickInput { .. }))
+            .count()
+    }
+
+    /// Total number of blocks destroyed at the end of each frame where some were destroyed
+    pub fn blocks_destroyed(&self) -> Vec<(usize, usize)> {
+        let mut destroyed = Vec::<(usize, usize)>::new();
+        for event in &self.events {
+            if let GameEvent::BlockDestroyed { frame, .. } = event {
+                match destroyed.last_mut() {
+                    Some((last, total)) if last == frame => *total += 1,
+                    _ => {
+                        let total = destroyed.last().map_or(0, |(_, total)| *total);
+                        destroyed.push((*frame, total + 1));
+                    }
+                }
+            }
+        }
+        destroyed
+    }
+
+    /// Points of every score increment, with the block that earned them
+    pub fn score_increments(&self) -> Vec<(Option<Vec2>, isize)> {
+        self.events
+            .iter()
+            .filter_map(|event| match event {
+                GameEvent::ScoreIncrement { points, block, .. } => Some((*block, *points)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Frame where the last block was destroyed
+    pub fn last_block_frame(&self) -> Option<usize> {
+        self.last_block_frame
+    }
+
+    /// Event log, one event per line
+    pub fn to_csv(&self) -> String {
+        let mut s = String::from("frame,event,x,y,value\n");
+        for event in &self.events {
+            match event {
+                GameEvent::JoystickInput { frame, joystick } => {
+                    writeln!(s, "{},joystick,,,{}", frame, *joystick as isize)
+                }
+                GameEvent::PaddleHit { frame, pos } => {
+                    writeln!(s, "{},paddle_hit,{},{},", frame, pos.x, pos.y)
+                }
+                GameEvent::BlockDestroyed { frame, pos } => {
+                    writeln!(s, "{},block_destroyed,{},{},", frame, pos.x, pos.y)
+                }
+                GameEvent::ScoreIncrement {
+                    frame,
+                    points,
+                    block: Some(pos),
+                } => writeln!(s, "{},score,{},{},{}", frame, pos.x, pos.y, points),
+                GameEvent::ScoreIncrement {
+                    frame,
+                    points,
+                    block: None,
+                } => writeln!(s, "{},score,,,{}", frame, points),
+            }
+            .unwrap();
+        }
+        s
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn play(stats: &mut GameStats, state: &mut ArcadeState, frame: usize, event: ArcadeEvent) {
+        stats.record(frame, &event, state);
+        state.apply(event);
+    }
+
+    #[test]
+    fn game_log() {
+        use ArcadeEvent::*;
+        let mut stats = GameStats::new();
+        let mut state = ArcadeState::default();
+        let tile = |x, y, tile| DrawTile {
+            pos: Vec2::new(x, y),
+            tile,
+        };
+        for event in [
+            tile(1, 0, TileType::Block),
+            tile(2, 0, TileType::Block),
+            tile(2, 4, TileType::Paddle),
+            tile(2, 2, TileType::Ball),
+        ] {
+            play(&mut stats, &mut state, 1, event);
+        }
+        stats.record_input(2, JoystickPosition::Left);
+        play(&mut stats, &mut state, 2, tile(1, 3, TileType::Ball));
+        stats.record_input(3, JoystickPosition::Neutral);
+        play(&mut stats, &mut state, 3, tile(2, 2, TileType::Ball));
+        play(&mut stats, &mut state, 3, tile(2, 0, TileType::Empty));
+        play(&mut stats, &mut state, 3, ScoreUpdate(7));
+        play(&mut stats, &mut state, 4, tile(1, 0, TileType::Empty));
+        play(&mut stats, &mut state, 4, ScoreUpdate(17));
+        play(&mut stats, &mut state, 5, ScoreUpdate(20));
+
+        assert_eq!(stats.paddle_hits(), 1);
+        assert_eq!(stats.inputs(), 2);
+        assert_eq!(stats.blocks_destroyed(), vec![(3, 1), (4, 2)]);
+        assert_eq!(
+            stats.score_increments(),
+            vec![
+                (Some(Vec2::new(2, 0)), 7),
+                (Some(Vec2::new(1, 0)), 10),
+                (None, 3)
+            ]
+        );
+        assert_eq!(stats.last_block_frame(), Some(4));
+        assert_eq!(
+            stats.to_csv(),
+            "frame,event,x,y,value\n2,joystick,,,-1\n3,joystick,,,0\n3,paddle_hit,1,3,\n\
+             3,block_destroyed,2,0,\n3,score,2,0,7\n4,block_destroyed,1,0,\n4,score,1,0,10\n\
+             5,score,,,3\n"
+        );
+
+        stats.rewind_to(3);
+        assert_eq!(stats.blocks_destroyed(), vec![(3, 1)]);
+        assert_eq!(stats.last_block_frame(), None);
+    }
+}
@@ -0,0 +1,331 @@
+use crate::arcade::{ArcadeState, JoystickPosition};
+#[cfg(unix)]
+use crate::terminal::{Key, RawTerminal};
+use geometry::Vec2;
+use std::cmp::Ordering;
+use std::io::{self, BufRead, Write};
+use std::str::FromStr;
+#[cfg(unix)]
+use std::time::{Duration, Instant};
+
+/// Chooses the joystick position every time the game asks for it
+pub trait JoystickStrategy {
+    /// Joystick position for the next frame, failing when the player cannot be reached
+    fn joystick(&mut self, state: &ArcadeState) -> io::Result<JoystickPosition>;
+
+    /// Whether the game should stop before the next frame
+    fn wants_to_quit(&self) -> bool {
+        false
+    }
+
+    /// Number of frames to go back instead of playing the joystick position just chosen
+    fn wants_to_rewind(&mut self) -> Option<usize> {
+        None
+    }
+}
+
+/// Joystick position moving the paddle from `paddle` toward `target`
+fn toward(paddle: isize, target: isize) -> JoystickPosition {
+    match paddle.cmp(&target) {
+        Ordering::Less => JoystickPosition::Right,
+        Ordering::Greater => JoystickPosition::Left,
+        Ordering::Equal => JoystickPosition::Neutral,
+    }
+}
+
+/// Keeps the paddle under the ball
+#[derive(Debug, Default)]
+pub struct GreedyStrategy;
+
+impl JoystickStrategy for GreedyStrategy {
+    fn joystick(&mut self, state: &ArcadeState) -> io::Result<JoystickPosition> {
+        Ok(toward(state.paddle_pos().x, state.ball_pos().x))
+    }
+}
+
+/// Moves the paddle to where the falling ball will reach it, bouncing on the side walls
+///
+/// Blocks in the way of the ball are not taken into account, the prediction is
+/// refreshed at every input anyway.
+#[derive(Debug, Default)]
+pub struct PredictionStrategy {
+    last_ball: Option<Vec2>,
+}
+
+impl PredictionStrategy {
+    fn landing(state: &ArcadeState, last: Vec2) -> isize {
+        let Vec2 { x, y } = state.ball_pos();
+        let dx = x - last.x;
+        let steps = state.paddle_pos().y - 1 - y;
+        let min_x = 1;
+        let max_x = state.width() as isize - 2;
+        if steps <= 0 || max_x <= min_x {
+            return x;
+        }
+        let period = 2 * (max_x - min_x);
+        let offset = (x + dx * steps - min_x).rem_euclid(period);
+        if offset > max_x - min_x {
+            min_x + period - offset
+        } else {
+            min_x + offset
+        }
+    }
+}
+
+impl JoystickStrategy for PredictionStrategy {
+    fn joystick(&mut self, state: &ArcadeState) -> io::Result<JoystickPosition> {
+        let ball = state.ball_pos();
+        let target = match self.last_ball {
+            Some(last) if ball.y > last.y => PredictionStrategy::landing(state, last),
+            _ => ball.x,
+        };
+        self.last_ball = Some(ball);
+        Ok(toward(state.paddle_pos().x, target))
+    }
+}
+
+/// Asks a player for every move, showing the game before each question
+///
+/// An empty line keeps the joystick neutral, `a` or `h` tilts it left and `d`
+/// or `l` tilts it right. The joystick stays neutral once the input is closed.
+pub struct KeyboardStrategy<R: BufRead, W: Write> {
+    input: R,
+    output: W,
+}
+
+impl<R: BufRead, W: Write> KeyboardStrategy<R, W> {
+    pub fn new(input: R, output: W) -> KeyboardStrategy<R, W> {
+        KeyboardStrategy { input, output }
+    }
+}
+
+impl<R: BufRead, W: Write> JoystickStrategy for KeyboardStrategy<R, W> {
+    fn joystick(&mut self, state: &ArcadeState) -> io::Result<JoystickPosition> {
+        loop {
+            write!(
+                self.output,
+                "{}Score: {}\n[a] left, [d] right, [enter] stay > ",
+                state,
+                state.score()
+            )?;
+            self.output.flush()?;
+            let mut line = String::new();
+            if self.input.read_line(&mut line)? == 0 {
+                return Ok(JoystickPosition::Neutral);
+            }
+            match line.trim() {
+                "" => return Ok(JoystickPosition::Neutral),
+                "a" | "h" => return Ok(JoystickPosition::Left),
+                "d" | "l" => return Ok(JoystickPosition::Right),
+                _ => (),
+            }
+        }
+    }
+}
+
+/// Player at the terminal, reading the keyboard for at most a frame
+///
+/// Arrow keys tilt the joystick while no key keeps it neutral, `p` or space
+/// pauses the game, `r` rewinds it by `rewind_frames` and `q` quits it.
+#[cfg(unix)]
+pub struct HumanStrategy {
+    terminal: RawTerminal,
+    frame_time: Duration,
+    rewind_frames: usize,
+    rewind: bool,
+    quit: bool,
+}
+
+#[cfg(unix)]
+impl HumanStrategy {
+    pub fn new(frame_time: Duration, rewind_frames: usize) -> io::Result<HumanStrategy> {
+        Ok(HumanStrategy {
+            terminal: RawTerminal::enable()?,
+            frame_time,
+            rewind_frames,
+            rewind: false,
+            quit: false,
+        })
+    }
+
+    /// Wait for the game to be resumed, returns false if the player quit instead
+    fn pause(&mut self) -> io::Result<bool> {
+        print!("-- Paused: [p] resume, [q] quit --");
+        io::stdout().flush()?;
+        loop {
+            let keys = self.terminal.read_keys(None)?;
+            if keys.contains(&Key::Quit) {
+                self.quit = true;
+                return Ok(false);
+            }
+            if keys.contains(&Key::Pause) {
+                return Ok(true);
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+impl JoystickStrategy for HumanStrategy {
+    fn joystick(&mut self, _: &ArcadeState) -> io::Result<JoystickPosition> {
+        let start = Instant::now();
+        let mut joystick = JoystickPosition::Neutral;
+        for key in self.terminal.read_keys(Some(self.frame_time))? {
+            match key {
+                Key::Left => joystick = JoystickPosition::Left,
+                Key::Right => joystick = JoystickPosition::Right,
+                Key::Quit => {
+                    self.quit = true;
+                    return Ok(JoystickPosition::Neutral);
+                }
+                Key::Pause => {
+                    if !self.pause()? {
+                        return Ok(JoystickPosition::Neutral);
+                    }
+                }
+                Key::Rewind => self.rewind = true,
+                Key::Other => (),
+            }
+        }
+        // Keep a steady pace when a key ended the wait early
+        if let Some(rest) = self.frame_time.checked_sub(start.elapsed()) {
+            std::thread::sleep(rest);
+        }
+        Ok(joystick)
+    }
+
+    fn wants_to_quit(&self) -> bool {
+        self.quit
+    }
+
+    fn wants_to_rewind(&mut self) -> Option<usize> {
+        if std::mem::take(&mut self.rewind) {
+            Some(self.rewind_frames)
+        } else {
+            None
+        }
+    }
+}
+
+/// Plays a fixed list of moves, then keeps the joystick neutral
+#[derive(Debug, Clone, Default)]
+pub struct ScriptedStrategy {
+    moves: Vec<JoystickPosition>,
+    next: usize,
+}
+
+impl ScriptedStrategy {
+    pub fn new(moves: Vec<JoystickPosition>) -> ScriptedStrategy {
+        ScriptedStrategy { moves, next: 0 }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidMove(pub char);
+
+impl std::error::Error for InvalidMove {}
+
+impl std::fmt::Display for InvalidMove {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid move {:?}, expected L, R or N", self.0)
+    }
+}
+
+/// Moves written as `L`, `R` and `N` for neutral, whitespace being ignored
+impl FromStr for ScriptedStrategy {
+    type Err = InvalidMove;
+
+    fn from_str(s: &str) -> Result<ScriptedStrategy, InvalidMove> {
+        let moves = s
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| match c.to_ascii_uppercase() {
+                'L' => Ok(JoystickPosition::Left),
+                'R' => Ok(JoystickPosition::Right),
+                'N' => Ok(JoystickPosition::Neutral),
+                _ => Err(InvalidMove(c)),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(ScriptedStrategy::new(moves))
+    }
+}
+
+impl JoystickStrategy for ScriptedStrategy {
+    fn joystick(&mut self, _: &ArcadeState) -> io::Result<JoystickPosition> {
+        let joystick = self
+            .moves
+            .get(self.next)
+            .copied()
+            .unwrap_or(JoystickPosition::Neutral);
+        self.next += 1;
+        Ok(joystick)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::arcade::TileType;
+
+    /// 10 wide board, paddle at (5, 10)
+    fn state((x, y): (isize, isize)) -> ArcadeState {
+        let mut state = ArcadeState::default();
+        state.draw(Vec2::new(9, 0), TileType::Wall);
+        state.draw(Vec2::new(5, 10), TileType::Paddle);
+        state.draw(Vec2::new(x, y), TileType::Ball);
+        state
+    }
+
+    #[test]
+    fn prediction() {
+        let mut strategy = PredictionStrategy::default();
+        assert_eq!(
+            strategy.joystick(&state((4, 5))).unwrap(),
+            JoystickPosition::Left
+        );
+        assert_eq!(
+            strategy.joystick(&state((5, 6))).unwrap(),
+            JoystickPosition::Right
+        );
+        assert_eq!(
+            PredictionStrategy::landing(&state((5, 6)), Vec2::new(4, 5)),
+            8
+        );
+        // Bounces on the right wall at x = 8, then on the left one at x = 1
+        assert_eq!(
+            PredictionStrategy::landing(&state((7, 6)), Vec2::new(6, 5)),
+            6
+        );
+        assert_eq!(
+            PredictionStrategy::landing(&state((2, 6)), Vec2::new(3, 5)),
+            3
+        );
+        // Rising: follow the ball
+        assert_eq!(
+            strategy.joystick(&state((5, 5))).unwrap(),
+            JoystickPosition::Neutral
+        );
+    }
+
+    #[test]
+    fn scripted_and_keyboard() {
+        let mut scripted = "LR n".parse::<ScriptedStrategy>().unwrap();
+        let state = state((1, 1));
+        let moves = (0..4)
+            .map(|_| scripted.joystick(&state).unwrap())
+            .collect::<Vec<_>>();
+        use JoystickPosition::*;
+        assert_eq!(moves, vec![Left, Right, Neutral, Neutral]);
+        assert_eq!(
+            "LX".parse::<ScriptedStrategy>().unwrap_err(),
+            InvalidMove('X')
+        );
+
+        let mut output = Vec::new();
+        let mut keyboard = KeyboardStrategy::new("d\nwhat\na\n\n".as_bytes(), &mut output);
+        let moves = (0..4)
+            .map(|_| keyboard.joystick(&state).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(moves, vec![Right, Left, Neutral, Neutral]);
+    }
+}
@@ -0,0 +1,130 @@
+//! Raw keyboard input on Unix terminals
+//!
+//! The terminal stops echoing keys and hands them over without waiting for a
+//! new line, until the `RawTerminal` is dropped.
+
+use std::io;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Left,
+    Right,
+    Pause,
+    Rewind,
+    Quit,
+    Other,
+}
+
+impl Key {
+    /// Keys typed in a chunk of terminal input, arrow keys being escape sequences
+    pub fn parse(bytes: &[u8]) -> Vec<Key> {
+        let mut keys = Vec::new();
+        let mut i = 0;
+        while i < bytes.len() {
+            match &bytes[i..] {
+                [0x1b, b'[', b'D', ..] | [0x1b, b'O', b'D', ..] => {
+                    keys.push(Key::Left);
+                    i += 3;
+                }
+                [0x1b, b'[', b'C', ..] | [0x1b, b'O', b'C', ..] => {
+                    keys.push(Key::Right);
+                    i += 3;
+                }
+                [b, ..] => {
+                    keys.push(match b {
+                        b'a' | b'h' => Key::Left,
+                        b'd' | b'l' => Key::Right,
+                        b'p' | b' ' => Key::Pause,
+                        b'r' | 0x7f => Key::Rewind,
+                        b'q' | 0x03 => Key::Quit,
+                        _ => Key::Other,
+                    });
+                    i += 1;
+                }
+                [] => unreachable!(),
+            }
+        }
+        keys
+    }
+}
+
+pub struct RawTerminal {
+    original: libc::termios,
+}
+
+impl RawTerminal {
+    pub fn enable() -> io::Result<RawTerminal> {
+        // Safety: termios is plain data, filled by tcgetattr before being used
+        let mut termios = unsafe { std::mem::zeroed::<libc::termios>() };
+        if unsafe { libc::tcgetattr(libc::STDIN_FILENO, &mut termios) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let original = termios;
+        termios.c_lflag &= !(libc::ICANON | libc::ECHO | libc::ISIG);
+        termios.c_cc[libc::VMIN] = 1;
+        termios.c_cc[libc::VTIME] = 0;
+        if unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &termios) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(RawTerminal { original })
+    }
+
+    /// Keys typed before the timeout, or until one is typed when there is no timeout
+    pub fn read_keys(&mut self, timeout: Option<Duration>) -> io::Result<Vec<Key>> {
+        let mut fd = libc::pollfd {
+            fd: libc::STDIN_FILENO,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        let timeout = timeout.map_or(-1, |t| t.as_millis().min(i32::MAX as u128) as i32);
+        match unsafe { libc::poll(&mut fd, 1, timeout) } {
+            -1 => return Err(io::Error::last_os_error()),
+            0 => return Ok(Vec::new()),
+            _ => (),
+        }
+        let mut buffer = [0u8; 64];
+        let read = unsafe {
+            libc::read(
+                libc::STDIN_FILENO,
+                buffer.as_mut_ptr() as *mut libc::c_void,
+                buffer.len(),
+            )
+        };
+        match read {
+            -1 => Err(io::Error::last_os_error()),
+            0 => Ok(vec![Key::Quit]),
+            n => Ok(Key::parse(&buffer[..n as usize])),
+        }
+    }
+}
+
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        unsafe {
+            libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &self.original);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_keys() {
+        assert_eq!(
+            Key::parse(b"\x1b[D\x1b[Cqpxr\x1bOD"),
+            vec![
+                Key::Left,
+                Key::Right,
+                Key::Quit,
+                Key::Pause,
+                Key::Other,
+                Key::Rewind,
+                Key::Left
+            ]
+        );
+        assert_eq!(Key::parse(b"\x1b"), vec![Key::Other]);
+    }
+}
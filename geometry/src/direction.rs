@@ -0,0 +1,93 @@
+use crate::vector::Vec2;
+
+/// One of the four directions on the screen, y growing downward
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Turn {
+    Left,
+    Right,
+}
+
+impl Direction {
+    /// Every direction, clockwise from up
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    pub fn turn(self, turn: Turn) -> Direction {
+        match turn {
+            Turn::Left => self.turn_left(),
+            Turn::Right => self.turn_right(),
+        }
+    }
+
+    /// Quarter turn counterclockwise
+    pub fn turn_left(self) -> Direction {
+        match self {
+            Direction::Up => Direction::Left,
+            Direction::Left => Direction::Down,
+            Direction::Down => Direction::Right,
+            Direction::Right => Direction::Up,
+        }
+    }
+
+    /// Quarter turn clockwise
+    pub fn turn_right(self) -> Direction {
+        match self {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+        }
+    }
+
+    pub fn opposite(self) -> Direction {
+        self.turn_right().turn_right()
+    }
+
+    /// Displacement of one step in this direction
+    pub fn offset(self) -> Vec2 {
+        match self {
+            Direction::Up => Vec2::new(0, -1),
+            Direction::Right => Vec2::new(1, 0),
+            Direction::Down => Vec2::new(0, 1),
+            Direction::Left => Vec2::new(-1, 0),
+        }
+    }
+
+    /// Position one step away in this direction
+    pub fn forward(self, pos: Vec2) -> Vec2 {
+        pos + self.offset()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rotation() {
+        let mut direction = Direction::Up;
+        for expected in &[Direction::Left, Direction::Down, Direction::Right] {
+            direction = direction.turn(Turn::Left);
+            assert_eq!(direction, *expected);
+        }
+        assert_eq!(direction.turn(Turn::Right), Direction::Down);
+        assert_eq!(Direction::Left.opposite(), Direction::Right);
+        let pos = Direction::ALL
+            .iter()
+            .fold(Vec2::ZERO, |pos, d| d.forward(pos));
+        assert_eq!(pos, Vec2::ZERO);
+        assert_eq!(Direction::Up.forward(Vec2::new(2, 2)), Vec2::new(2, 1));
+    }
+}
@@ -0,0 +1,385 @@
+use crate::direction::Direction;
+use crate::vector::Vec2;
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+/// Offsets of the eight cells around a cell, row by row
+const AROUND: [Vec2; 8] = [
+    Vec2::new(-1, -1),
+    Vec2::new(0, -1),
+    Vec2::new(1, -1),
+    Vec2::new(-1, 0),
+    Vec2::new(1, 0),
+    Vec2::new(-1, 1),
+    Vec2::new(0, 1),
+    Vec2::new(1, 1),
+];
+
+/// Rectangle between two corners, both included
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Bounds {
+    pub min: Vec2,
+    pub max: Vec2,
+}
+
+impl Bounds {
+    /// Smallest rectangle holding every point
+    pub fn from_points<I: IntoIterator<Item = Vec2>>(points: I) -> Option<Bounds> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        let mut bounds = Bounds {
+            min: first,
+            max: first,
+        };
+        for point in points {
+            bounds.extend(point);
+        }
+        Some(bounds)
+    }
+
+    /// Grow the rectangle to hold `point`
+    pub fn extend(&mut self, point: Vec2) {
+        self.min = Vec2::new(self.min.x.min(point.x), self.min.y.min(point.y));
+        self.max = Vec2::new(self.max.x.max(point.x), self.max.y.max(point.y));
+    }
+
+    /// Rectangle of the positions in both rectangles
+    pub fn intersection(&self, other: &Bounds) -> Option<Bounds> {
+        let min = Vec2::new(self.min.x.max(other.min.x), self.min.y.max(other.min.y));
+        let max = Vec2::new(self.max.x.min(other.max.x), self.max.y.min(other.max.y));
+        if min.x > max.x || min.y > max.y {
+            return None;
+        }
+        Some(Bounds { min, max })
+    }
+
+    pub fn contains(&self, point: Vec2) -> bool {
+        (self.min.x..=self.max.x).contains(&point.x) && (self.min.y..=self.max.y).contains(&point.y)
+    }
+
+    pub fn width(&self) -> usize {
+        (self.max.x - self.min.x + 1) as usize
+    }
+
+    pub fn height(&self) -> usize {
+        (self.max.y - self.min.y + 1) as usize
+    }
+
+    /// Every position of the rectangle, row by row
+    pub fn positions(&self) -> impl Iterator<Item = Vec2> {
+        let Bounds { min, max } = *self;
+        (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| Vec2::new(x, y)))
+    }
+}
+
+/// Rectangle of cells, the top-left one being at (0, 0)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Grid<T> {
+        Grid {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /// Grow the grid to at least `width` by `height`, new cells being `fill`
+    pub fn expand(&mut self, width: usize, height: usize, fill: T) {
+        let width = width.max(self.width);
+        let height = height.max(self.height);
+        if (width, height) == (self.width, self.height) {
+            return;
+        }
+        let mut cells = Vec::with_capacity(width * height);
+        for row in self.rows() {
+            cells.extend_from_slice(row);
+            cells.resize(cells.len() + width - self.width, fill.clone());
+        }
+        cells.resize(width * height, fill);
+        *self = Grid {
+            width,
+            height,
+            cells,
+        };
+    }
+}
+
+impl<T> Grid<T> {
+    /// Grid made of rows of the same length
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Option<Grid<T>> {
+        let width = rows.first().map_or(0, |row| row.len());
+        if rows.iter().any(|row| row.len() != width) {
+            return None;
+        }
+        let height = if width == 0 { 0 } else { rows.len() };
+        Some(Grid {
+            width,
+            height,
+            cells: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn bounds(&self) -> Option<Bounds> {
+        if self.cells.is_empty() {
+            return None;
+        }
+        Some(Bounds {
+            min: Vec2::ZERO,
+            max: Vec2::new(self.width as isize - 1, self.height as isize - 1),
+        })
+    }
+
+    pub fn contains(&self, pos: Vec2) -> bool {
+        pos.x >= 0 && pos.y >= 0 && (pos.x as usize) < self.width && (pos.y as usize) < self.height
+    }
+
+    fn index_of(&self, pos: Vec2) -> Option<usize> {
+        if self.contains(pos) {
+            Some(pos.y as usize * self.width + pos.x as usize)
+        } else {
+            None
+        }
+    }
+
+    pub fn get(&self, pos: Vec2) -> Option<&T> {
+        self.index_of(pos).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, pos: Vec2) -> Option<&mut T> {
+        self.index_of(pos).map(move |i| &mut self.cells[i])
+    }
+
+    /// Replace a cell, returns its previous value or None outside of the grid
+    pub fn set(&mut self, pos: Vec2, value: T) -> Option<T> {
+        self.get_mut(pos).map(|cell| std::mem::replace(cell, value))
+    }
+
+    /// Rows of cells from top to bottom
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// Every cell with its position, row by row
+    pub fn iter(&self) -> impl Iterator<Item = (Vec2, &T)> {
+        let width = self.width.max(1);
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(i, cell)| (Vec2::new((i % width) as isize, (i / width) as isize), cell))
+    }
+
+    /// Positions up, right, down and left of `pos` that are in the grid
+    pub fn neighbours(&self, pos: Vec2) -> impl Iterator<Item = Vec2> + '_ {
+        Direction::ALL
+            .iter()
+            .map(move |d| d.forward(pos))
+            .filter(move |p| self.contains(*p))
+    }
+
+    /// Positions of the eight cells around `pos` that are in the grid, diagonals included
+    pub fn neighbours8(&self, pos: Vec2) -> impl Iterator<Item = Vec2> + '_ {
+        AROUND
+            .iter()
+            .map(move |offset| pos + *offset)
+            .filter(move |p| self.contains(*p))
+    }
+
+    /// One line of text per row, each cell drawn as a character
+    pub fn render<F: Fn(&T) -> char>(&self, draw: F) -> String {
+        let mut s = String::with_capacity((self.width + 1) * self.height);
+        for row in self.rows().take(self.height) {
+            s.extend(row.iter().map(&draw));
+            s.push('\n');
+        }
+        s
+    }
+}
+
+impl<T> Index<Vec2> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Vec2) -> &T {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("Position {} out of the grid", pos))
+    }
+}
+
+impl<T> IndexMut<Vec2> for Grid<T> {
+    fn index_mut(&mut self, pos: Vec2) -> &mut T {
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("Position {} out of the grid", pos))
+    }
+}
+
+impl<T: Copy + Into<char>> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.render(|cell| (*cell).into()))
+    }
+}
+
+/// Cells at any position, including negative ones, only the ones set being stored
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Vec2, T>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> SparseGrid<T> {
+        SparseGrid {
+            cells: HashMap::new(),
+        }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> SparseGrid<T> {
+        SparseGrid::default()
+    }
+
+    pub fn get(&self, pos: Vec2) -> Option<&T> {
+        self.cells.get(&pos)
+    }
+
+    pub fn get_mut(&mut self, pos: Vec2) -> Option<&mut T> {
+        self.cells.get_mut(&pos)
+    }
+
+    /// Set a cell, returns its previous value
+    pub fn insert(&mut self, pos: Vec2, value: T) -> Option<T> {
+        self.cells.insert(pos, value)
+    }
+
+    pub fn remove(&mut self, pos: Vec2) -> Option<T> {
+        self.cells.remove(&pos)
+    }
+
+    pub fn contains(&self, pos: Vec2) -> bool {
+        self.cells.contains_key(&pos)
+    }
+
+    /// Number of cells set
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Cells set, in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = (Vec2, &T)> {
+        self.cells.iter().map(|(pos, cell)| (*pos, cell))
+    }
+
+    /// Smallest rectangle holding every cell set
+    pub fn bounds(&self) -> Option<Bounds> {
+        Bounds::from_points(self.cells.keys().copied())
+    }
+
+    /// Cells set up, right, down and left of `pos`
+    pub fn neighbours(&self, pos: Vec2) -> impl Iterator<Item = (Vec2, &T)> + '_ {
+        Direction::ALL.iter().filter_map(move |d| {
+            let pos = d.forward(pos);
+            self.get(pos).map(|cell| (pos, cell))
+        })
+    }
+
+    /// One line of text per row of the bounds, cells not set being drawn as `empty`
+    pub fn render<F: Fn(&T) -> char>(&self, empty: char, draw: F) -> String {
+        let mut s = String::new();
+        if let Some(bounds) = self.bounds() {
+            for pos in bounds.positions() {
+                s.push(self.get(pos).map_or(empty, &draw));
+                if pos.x == bounds.max.x {
+                    s.push('\n');
+                }
+            }
+        }
+        s
+    }
+}
+
+impl<T> std::iter::FromIterator<(Vec2, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Vec2, T)>>(iter: I) -> SparseGrid<T> {
+        SparseGrid {
+            cells: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<T: Copy + Into<char>> fmt::Display for SparseGrid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.render(' ', |cell| (*cell).into()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn dense() {
+        let rows = vec![vec!['#', '.', '.'], vec!['.', '#', '.']];
+        let mut grid = Grid::from_rows(rows).unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[Vec2::new(1, 1)], '#');
+        assert_eq!(grid.get(Vec2::new(3, 0)), None);
+        assert_eq!(grid.set(Vec2::new(2, 0), '#'), Some('.'));
+        assert_eq!(grid.set(Vec2::new(-1, 0), '#'), None);
+        assert_eq!(grid.to_string(), "#.#\n.#.\n");
+        let neighbours = grid.neighbours(Vec2::new(0, 0)).collect::<Vec<_>>();
+        assert_eq!(neighbours, vec![Vec2::new(1, 0), Vec2::new(0, 1)]);
+        assert_eq!(grid.neighbours8(Vec2::new(1, 1)).count(), 5);
+        assert_eq!(
+            grid.iter()
+                .filter(|(_, c)| **c == '#')
+                .map(|(p, _)| p)
+                .collect::<Vec<_>>(),
+            vec![Vec2::new(0, 0), Vec2::new(2, 0), Vec2::new(1, 1)]
+        );
+
+        grid.expand(4, 3, ' ');
+        assert_eq!(grid.to_string(), "#.# \n.#. \n    \n");
+        assert!(Grid::from_rows(vec![vec![1, 2], vec![3]]).is_none());
+    }
+
+    #[test]
+    fn sparse() {
+        let mut grid = SparseGrid::new();
+        grid.insert(Vec2::new(-1, -1), '#');
+        grid.insert(Vec2::new(1, 0), '#');
+        grid.insert(Vec2::new(0, 0), '.');
+        let bounds = grid.bounds().unwrap();
+        assert_eq!(bounds.min, Vec2::new(-1, -1));
+        assert_eq!((bounds.width(), bounds.height()), (3, 2));
+        assert!(bounds.contains(Vec2::new(0, -1)));
+        let column = Bounds::from_points(vec![Vec2::new(0, -3), Vec2::new(0, 3)]).unwrap();
+        let overlap = bounds.intersection(&column).unwrap();
+        assert_eq!(
+            (overlap.min, overlap.max),
+            (Vec2::new(0, -1), Vec2::new(0, 0))
+        );
+        assert_eq!(
+            overlap.intersection(&Bounds::from_points(vec![Vec2::new(1, 1)]).unwrap()),
+            None
+        );
+        assert_eq!(grid.to_string(), "#  \n .#\n");
+        assert_eq!(grid.neighbours(Vec2::new(0, -1)).count(), 2);
+        assert_eq!(grid.render('?', |_| 'x'), "x??\n?xx\n");
+    }
+}
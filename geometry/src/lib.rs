@@ -0,0 +1,12 @@
+//! Integer geometry shared by the puzzles working on grids
+//!
+//! Positions are 2D or 3D integer vectors. On the screen, x grows to the right
+//! and y grows downward, so `Direction::Up` decreases y.
+
+mod direction;
+mod grid;
+mod vector;
+
+pub use self::direction::{Direction, Turn};
+pub use self::grid::{Bounds, Grid, SparseGrid};
+pub use self::vector::{Vec2, Vec3};
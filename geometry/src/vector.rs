@@ -0,0 +1,192 @@
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// Position or displacement on a plane
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Vec2 {
+    pub x: isize,
+    pub y: isize,
+}
+
+/// Position or displacement in space
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Vec3 {
+    pub x: isize,
+    pub y: isize,
+    pub z: isize,
+}
+
+impl Vec2 {
+    pub const ZERO: Vec2 = Vec2 { x: 0, y: 0 };
+
+    pub const fn new(x: isize, y: isize) -> Vec2 {
+        Vec2 { x, y }
+    }
+
+    /// Sum of the absolute coordinates
+    pub fn manhattan(self) -> usize {
+        self.x.unsigned_abs() + self.y.unsigned_abs()
+    }
+
+    /// Largest absolute coordinate
+    pub fn chebyshev(self) -> usize {
+        self.x.unsigned_abs().max(self.y.unsigned_abs())
+    }
+
+    pub fn manhattan_distance(self, other: Vec2) -> usize {
+        (self - other).manhattan()
+    }
+
+    pub fn chebyshev_distance(self, other: Vec2) -> usize {
+        (self - other).chebyshev()
+    }
+
+    /// Sign of each coordinate
+    pub fn signum(self) -> Vec2 {
+        Vec2::new(self.x.signum(), self.y.signum())
+    }
+}
+
+impl Vec3 {
+    pub const ZERO: Vec3 = Vec3 { x: 0, y: 0, z: 0 };
+
+    pub const fn new(x: isize, y: isize, z: isize) -> Vec3 {
+        Vec3 { x, y, z }
+    }
+
+    /// Sum of the absolute coordinates
+    pub fn manhattan(self) -> usize {
+        self.x.unsigned_abs() + self.y.unsigned_abs() + self.z.unsigned_abs()
+    }
+
+    /// Largest absolute coordinate
+    pub fn chebyshev(self) -> usize {
+        self.x
+            .unsigned_abs()
+            .max(self.y.unsigned_abs())
+            .max(self.z.unsigned_abs())
+    }
+
+    pub fn manhattan_distance(self, other: Vec3) -> usize {
+        (self - other).manhattan()
+    }
+
+    pub fn chebyshev_distance(self, other: Vec3) -> usize {
+        (self - other).chebyshev()
+    }
+
+    /// Sign of each coordinate
+    pub fn signum(self) -> Vec3 {
+        Vec3::new(self.x.signum(), self.y.signum(), self.z.signum())
+    }
+}
+
+macro_rules! impl_ops {
+    ($vec:ident, $($field:ident),+) => {
+        impl Add for $vec {
+            type Output = $vec;
+
+            fn add(self, other: $vec) -> $vec {
+                $vec { $($field: self.$field + other.$field),+ }
+            }
+        }
+
+        impl Sub for $vec {
+            type Output = $vec;
+
+            fn sub(self, other: $vec) -> $vec {
+                $vec { $($field: self.$field - other.$field),+ }
+            }
+        }
+
+        impl Mul<isize> for $vec {
+            type Output = $vec;
+
+            fn mul(self, n: isize) -> $vec {
+                $vec { $($field: self.$field * n),+ }
+            }
+        }
+
+        impl Neg for $vec {
+            type Output = $vec;
+
+            fn neg(self) -> $vec {
+                $vec { $($field: -self.$field),+ }
+            }
+        }
+
+        impl AddAssign for $vec {
+            fn add_assign(&mut self, other: $vec) {
+                *self = *self + other;
+            }
+        }
+
+        impl SubAssign for $vec {
+            fn sub_assign(&mut self, other: $vec) {
+                *self = *self - other;
+            }
+        }
+    };
+}
+
+impl_ops!(Vec2, x, y);
+impl_ops!(Vec3, x, y, z);
+
+impl From<(isize, isize)> for Vec2 {
+    fn from((x, y): (isize, isize)) -> Vec2 {
+        Vec2 { x, y }
+    }
+}
+
+impl From<Vec2> for (isize, isize) {
+    fn from(v: Vec2) -> (isize, isize) {
+        (v.x, v.y)
+    }
+}
+
+impl From<(isize, isize, isize)> for Vec3 {
+    fn from((x, y, z): (isize, isize, isize)) -> Vec3 {
+        Vec3 { x, y, z }
+    }
+}
+
+impl fmt::Display for Vec2 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<x={}, y={}>", self.x, self.y)
+    }
+}
+
+impl fmt::Display for Vec3 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<x={}, y={}, z={}>", self.x, self.y, self.z)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn distances() {
+        let a = Vec2::new(1, -5);
+        let b = Vec2::new(6, 2);
+        assert_eq!(a.manhattan_distance(b), 12);
+        assert_eq!(a.chebyshev_distance(b), 7);
+        let c = Vec3::new(-1, 2, -3);
+        assert_eq!(c.manhattan(), 6);
+        assert_eq!(c.chebyshev_distance(Vec3::ZERO), 3);
+    }
+
+    #[test]
+    fn operations() {
+        let mut a = Vec2::new(1, 2);
+        a += Vec2::new(3, -4);
+        assert_eq!(a, Vec2::new(4, -2));
+        assert_eq!(a - Vec2::new(4, 4), Vec2::new(0, -6));
+        assert_eq!(-a * 2, Vec2::new(-8, 4));
+        assert_eq!(a.signum(), Vec2::new(1, -1));
+        assert_eq!(Vec3::new(1, 2, 3) + Vec3::new(1, 1, 1), Vec3::new(2, 3, 4));
+        assert_eq!(Vec3::new(-7, 17, -11).to_string(), "<x=-7, y=17, z=-11>");
+        assert_eq!(Vec2::from((3, 4)), Vec2::new(3, 4));
+    }
+}
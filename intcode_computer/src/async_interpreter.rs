@@ -0,0 +1,208 @@
+use crate::dialect::Dialect;
+use crate::instruction::{Instruction, InstructionResult, InvalidInstruction};
+use crate::interpreter::{Interpreter, InterpreterError, StopReason};
+use crate::program::Program;
+use futures::{Sink, SinkExt, Stream, StreamExt};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::fmt;
+use std::rc::Rc;
+
+/// Interpreter reading its inputs from a `Stream` and writing its outputs to a `Sink`
+///
+/// The program runs synchronously until it needs an input that has not arrived yet,
+/// then the future yields until the stream produces one. Outputs are buffered one at
+/// a time: the program is paused until its previous output has been sent.
+pub struct AsyncInterpreter {
+    interpreter: Interpreter,
+    inputs: Rc<RefCell<VecDeque<isize>>>,
+    output: Rc<RefCell<Option<isize>>>,
+}
+
+#[derive(Debug)]
+pub enum AsyncInterpreterError<E> {
+    Interpreter(InterpreterError),
+    InputClosed,
+    Output(E),
+}
+
+impl<E: fmt::Debug + fmt::Display> std::error::Error for AsyncInterpreterError<E> {}
+
+impl<E: fmt::Display> fmt::Display for AsyncInterpreterError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AsyncInterpreterError::Interpreter(e) => write!(f, "{}", e),
+            AsyncInterpreterError::InputClosed => {
+                write!(f, "Input stream ended while the program waits for input")
+            }
+            AsyncInterpreterError::Output(e) => write!(f, "Could not send output: {}", e),
+        }
+    }
+}
+
+impl<E> From<InterpreterError> for AsyncInterpreterError<E> {
+    fn from(e: InterpreterError) -> AsyncInterpreterError<E> {
+        AsyncInterpreterError::Interpreter(e)
+    }
+}
+
+impl AsyncInterpreter {
+    pub fn new(program: Program) -> AsyncInterpreter {
+        AsyncInterpreter::with_dialect(program, &Dialect::standard()).unwrap()
+    }
+
+    pub fn with_dialect(
+        program: Program,
+        dialect: &Dialect,
+    ) -> Result<AsyncInterpreter, InvalidInstruction> {
+        let mut interpreter = Interpreter::without_io(program, dialect)?;
+        let inputs = Rc::new(RefCell::new(VecDeque::new()));
+        let output = Rc::new(RefCell::new(None));
+
+        // Instruction: input, blocking until the stream produced a value
+        interpreter.add_instruction(Instruction::new(3, 1, {
+            let inputs = inputs.clone();
+            move |mut params| match inputs.borrow_mut().pop_front() {
+                Some(input) => {
+                    params[0].write(input)?;
+                    Ok(InstructionResult::Continue)
+                }
+                None => Ok(InstructionResult::Block),
+            }
+        })?);
+
+        // Instruction: output, blocking until the previous output was sent
+        interpreter.add_instruction(Instruction::new(4, 1, {
+            let output = output.clone();
+            move |params| {
+                let mut output = output.borrow_mut();
+                if output.is_some() {
+                    return Ok(InstructionResult::Block);
+                }
+                *output = Some(params[0].read()?);
+                Ok(InstructionResult::Continue)
+            }
+        })?);
+
+        Ok(AsyncInterpreter {
+            interpreter,
+            inputs,
+            output,
+        })
+    }
+
+    pub fn interpreter(&self) -> &Interpreter {
+        &self.interpreter
+    }
+
+    pub fn interpreter_mut(&mut self) -> &mut Interpreter {
+        &mut self.interpreter
+    }
+
+    pub fn into_interpreter(self) -> Interpreter {
+        self.interpreter
+    }
+
+    /// Run the program until it halts, feeding it from `input` and forwarding its outputs
+    pub async fn run<S, K>(
+        &mut self,
+        mut input: S,
+        mut output: K,
+    ) -> Result<(), AsyncInterpreterError<K::Error>>
+    where
+        S: Stream<Item = isize> + Unpin,
+        K: Sink<isize> + Unpin,
+    {
+        loop {
+            let reason = self.interpreter.run()?;
+            let value = self.output.borrow_mut().take();
+            if let Some(value) = value {
+                output
+                    .send(value)
+                    .await
+                    .map_err(AsyncInterpreterError::Output)?;
+                continue;
+            }
+            match reason {
+                StopReason::Halted => return Ok(()),
+                StopReason::Blocked => match input.next().await {
+                    Some(value) => self.inputs.borrow_mut().push_back(value),
+                    None => return Err(AsyncInterpreterError::InputClosed),
+                },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use futures::channel::mpsc;
+    use futures::executor::{block_on, LocalPool};
+    use futures::stream;
+    use futures::task::LocalSpawnExt;
+
+    // Outputs three times each input, until it reads 0
+    const TRIPLE: &str = "3,20,1006,20,14,1002,20,3,21,4,21,1105,1,0,99";
+
+    #[test]
+    fn single_machine() {
+        let mut machine = AsyncInterpreter::new(TRIPLE.parse::<Program>().unwrap());
+        let mut outputs = Vec::new();
+        block_on(machine.run(stream::iter(vec![1, 2, 5, 0]), &mut outputs)).unwrap();
+        assert_eq!(outputs, vec![3, 6, 15]);
+
+        let mut machine = AsyncInterpreter::new(TRIPLE.parse::<Program>().unwrap());
+        let mut outputs = Vec::new();
+        let result = block_on(machine.run(stream::iter(vec![4]), &mut outputs));
+        assert!(matches!(result, Err(AsyncInterpreterError::InputClosed)));
+        assert_eq!(outputs, vec![12]);
+    }
+
+    #[test]
+    fn feedback_loop() {
+        let program = "3,26,1001,26,-4,26,3,27,1002,27,2,27,1,27,26,27,4,27,1001,28,-1,28,\
+                       1005,28,6,99,0,0,5"
+            .parse::<Program>()
+            .unwrap();
+        let phases = [9, 8, 7, 6, 5];
+        let (senders, receivers): (Vec<_>, Vec<_>) =
+            phases.iter().map(|_| mpsc::unbounded::<isize>()).unzip();
+        for (sender, phase) in senders.iter().zip(&phases) {
+            sender.unbounded_send(*phase).unwrap();
+        }
+        senders[0].unbounded_send(0).unwrap();
+
+        let mut pool = LocalPool::new();
+        let spawner = pool.spawner();
+        let (feedback_sender, mut feedback_receiver) = mpsc::unbounded::<isize>();
+        for (i, input) in receivers.into_iter().enumerate() {
+            let mut machine = AsyncInterpreter::new(program.clone());
+            let output = match senders.get(i + 1) {
+                Some(sender) => sender.clone(),
+                None => feedback_sender.clone(),
+            };
+            spawner
+                .spawn_local(async move { machine.run(input, output).await.unwrap() })
+                .unwrap();
+        }
+
+        // Route the last amplifier back to the first one, keeping its last signal
+        let thrusters = Rc::new(RefCell::new(None));
+        let first = senders[0].clone();
+        let last_signal = thrusters.clone();
+        spawner
+            .spawn_local(async move {
+                while let Some(signal) = feedback_receiver.next().await {
+                    *last_signal.borrow_mut() = Some(signal);
+                    let _ = first.unbounded_send(signal);
+                }
+            })
+            .unwrap();
+        drop(senders);
+        drop(feedback_sender);
+        pool.run();
+
+        assert_eq!(*thrusters.borrow(), Some(139629729));
+    }
+}
@@ -0,0 +1,311 @@
+//! Generic Intcode runner
+//!
+//! Usage: `intcode [OPTIONS] <program>`
+//!
+//! Options:
+//!   -i, --input <values>        Comma-separated input values, queued in order
+//!   -f, --input-file <file>     File of comma or whitespace separated input values
+//!   -o, --output <format>       Output format: numbers (default), ascii or json
+//!   -s, --set <address=value>   Patch the program memory before running
+//!   -n, --max-steps <steps>     Stop with an error after this many instructions
+//!   -D, --dialect <name>        Instruction set profile: standard (default), debug or extended
+//!   -d, --dump-memory           Print the final memory once the program halts
+//!       --hexdump               Print the final memory with decoded instructions annotated
+//!       --diff                  Print the memory cells changed by the run, patches included
+//!
+//! Input values given on the command line or in files are consumed first, then
+//! the remaining inputs are read from stdin, one or more integers per line.
+//! A program stopped by a halt-with-code instruction exits with that code.
+//!
+//! Examples:
+//!   intcode Day02/input --set 1=12 --set 2=2 --dump-memory
+//!   intcode Day02/input --set 1=12 --set 2=2 --diff
+//!   intcode Day05/input --input 5
+//!   intcode Day09/input --input 2
+
+use intcode_computer::{
+    diff, Dialect, HexDump, Instruction, InstructionResult, Interpreter, Program,
+};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::convert::TryFrom;
+use std::fmt;
+use std::io::BufRead;
+use std::rc::Rc;
+
+#[derive(Debug)]
+enum ArgsError {
+    MissingProgram,
+    MissingValue(String),
+    UnknownOption(String),
+    InvalidValue(String),
+    InvalidPatch(String),
+    InvalidOutputFormat(String),
+    UnknownDialect(String),
+}
+
+impl std::error::Error for ArgsError {}
+
+impl fmt::Display for ArgsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use ArgsError::*;
+        match self {
+            MissingProgram => write!(f, "No program file given"),
+            MissingValue(opt) => write!(f, "Option {} expects a value", opt),
+            UnknownOption(opt) => write!(f, "Unknown option: {}", opt),
+            InvalidValue(v) => write!(f, "Invalid integer value: {}", v),
+            InvalidPatch(p) => write!(f, "Invalid memory patch (expected address=value): {}", p),
+            InvalidOutputFormat(o) => {
+                write!(
+                    f,
+                    "Invalid output format (expected numbers, ascii or json): {}",
+                    o
+                )
+            }
+            UnknownDialect(d) => write!(f, "Unknown dialect: {}", d),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum InputError {
+    EndOfInput,
+    Read(std::io::Error),
+    InvalidValue(ArgsError),
+}
+
+impl std::error::Error for InputError {}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::EndOfInput => write!(f, "No more input values available"),
+            InputError::Read(e) => write!(f, "Could not read stdin: {}", e),
+            InputError::InvalidValue(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+/// Next input value, read from stdin once the queued ones are consumed
+fn next_input(inputs: &mut VecDeque<isize>) -> Result<isize, InputError> {
+    loop {
+        if let Some(input) = inputs.pop_front() {
+            return Ok(input);
+        }
+        let mut line = String::new();
+        let read = std::io::stdin()
+            .lock()
+            .read_line(&mut line)
+            .map_err(InputError::Read)?;
+        if read == 0 {
+            return Err(InputError::EndOfInput);
+        }
+        inputs.extend(parse_values(&line).map_err(InputError::InvalidValue)?);
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum OutputFormat {
+    Numbers,
+    Ascii,
+    Json,
+}
+
+struct Options {
+    program: String,
+    inputs: VecDeque<isize>,
+    output: OutputFormat,
+    patches: Vec<(usize, isize)>,
+    max_steps: Option<usize>,
+    dialect: Dialect,
+    dump_memory: bool,
+    hexdump: bool,
+    diff: bool,
+}
+
+fn parse_values(s: &str) -> Result<Vec<isize>, ArgsError> {
+    s.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|v| !v.is_empty())
+        .map(|v| {
+            v.parse::<isize>()
+                .map_err(|_| ArgsError::InvalidValue(v.to_owned()))
+        })
+        .collect()
+}
+
+fn parse_patch(s: &str) -> Result<(usize, isize), ArgsError> {
+    let mut parts = s.splitn(2, '=');
+    let address = parts.next().and_then(|a| a.trim().parse::<usize>().ok());
+    let value = parts.next().and_then(|v| v.trim().parse::<isize>().ok());
+    match (address, value) {
+        (Some(address), Some(value)) => Ok((address, value)),
+        _ => Err(ArgsError::InvalidPatch(s.to_owned())),
+    }
+}
+
+fn parse_args() -> Result<Options, Box<dyn std::error::Error>> {
+    let mut program = None;
+    let mut options = Options {
+        program: String::new(),
+        inputs: VecDeque::new(),
+        output: OutputFormat::Numbers,
+        patches: Vec::new(),
+        max_steps: None,
+        dialect: Dialect::standard(),
+        dump_memory: false,
+        hexdump: false,
+        diff: false,
+    };
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| ArgsError::MissingValue(arg.clone()))
+        };
+        match arg.as_str() {
+            "-i" | "--input" => options.inputs.extend(parse_values(&value()?)?),
+            "-f" | "--input-file" => {
+                let content = std::fs::read_to_string(value()?)?;
+                options.inputs.extend(parse_values(&content)?);
+            }
+            "-o" | "--output" => {
+                options.output = match value()?.as_str() {
+                    "numbers" => OutputFormat::Numbers,
+                    "ascii" => OutputFormat::Ascii,
+                    "json" => OutputFormat::Json,
+                    other => return Err(ArgsError::InvalidOutputFormat(other.to_owned()).into()),
+                }
+            }
+            "-s" | "--set" => options.patches.push(parse_patch(&value()?)?),
+            "-n" | "--max-steps" => {
+                let steps = value()?;
+                let steps = steps
+                    .parse::<usize>()
+                    .map_err(|_| ArgsError::InvalidValue(steps.clone()))?;
+                options.max_steps = Some(steps);
+            }
+            "-D" | "--dialect" => {
+                let name = value()?;
+                options.dialect =
+                    Dialect::from_name(&name).ok_or(ArgsError::UnknownDialect(name))?;
+            }
+            "-d" | "--dump-memory" => options.dump_memory = true,
+            "--hexdump" => options.hexdump = true,
+            "--diff" => options.diff = true,
+            opt if opt.starts_with('-') && opt != "-" => {
+                return Err(ArgsError::UnknownOption(opt.to_owned()).into())
+            }
+            _ => program = Some(arg),
+        }
+    }
+    options.program = program.ok_or(ArgsError::MissingProgram)?;
+    Ok(options)
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let options = parse_args()?;
+    let input = std::fs::read_to_string(&options.program)?;
+    let initial_program = input.parse::<Program>()?;
+    let mut program = initial_program.clone();
+    for (address, value) in &options.patches {
+        program.write(*address, *value)?;
+    }
+
+    let format = options.output;
+    let outputs = Rc::new(RefCell::new(Vec::new()));
+    let mut interpreter = Interpreter::without_io(program, &options.dialect)?;
+
+    // Instruction: output, printed right away or collected for the JSON array
+    interpreter.add_instruction(Instruction::new(4, 1, {
+        let outputs = outputs.clone();
+        move |params| {
+            let output = params[0].read()?;
+            match format {
+                OutputFormat::Numbers => println!("{}", output),
+                OutputFormat::Ascii => match output {
+                    0..=127 => print!("{}", output as u8 as char),
+                    _ => println!("{}", output),
+                },
+                OutputFormat::Json => outputs.borrow_mut().push(output),
+            }
+            Ok(InstructionResult::Continue)
+        }
+    })?);
+
+    // Instruction: input, blocking the program on a read failure kept for main to report
+    let input_error = Rc::new(RefCell::new(None));
+    let mut inputs = options.inputs;
+    interpreter.add_instruction(Instruction::new(3, 1, {
+        let input_error = input_error.clone();
+        move |mut params| match next_input(&mut inputs) {
+            Ok(input) => {
+                params[0].write(input)?;
+                Ok(InstructionResult::Continue)
+            }
+            Err(e) => {
+                *input_error.borrow_mut() = Some(e);
+                Ok(InstructionResult::Block)
+            }
+        }
+    })?);
+    interpreter.set_step_limit(options.max_steps);
+    interpreter.execute()?;
+    if let Some(e) = input_error.borrow_mut().take() {
+        return Err(e.into());
+    }
+
+    if format == OutputFormat::Json {
+        let outputs = outputs
+            .borrow()
+            .iter()
+            .map(|o| o.to_string())
+            .collect::<Vec<_>>();
+        println!("[{}]", outputs.join(","));
+    }
+    let exit_code = interpreter.exit_code();
+    let final_program = interpreter.into_program();
+    if options.dump_memory {
+        println!("{}", final_program);
+    }
+    if options.hexdump {
+        print!("{}", HexDump::new(&final_program));
+    }
+    if options.diff {
+        for change in diff(&initial_program, &final_program) {
+            println!("{}", change);
+        }
+    }
+    if let Some(code) = exit_code {
+        let code = i32::try_from(code)
+            .map_err(|_| format!("Exit code {} does not fit a process exit status", code))?;
+        std::process::exit(code);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn values() {
+        assert_eq!(parse_values("1,-2, 3\n4  5").unwrap(), vec![1, -2, 3, 4, 5]);
+        assert_eq!(parse_values(" \n").unwrap(), Vec::<isize>::new());
+        match parse_values("1,x2") {
+            Err(ArgsError::InvalidValue(v)) => assert_eq!(v, "x2"),
+            other => panic!("Unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn patches() {
+        assert_eq!(parse_patch("1=12").unwrap(), (1, 12));
+        assert_eq!(parse_patch(" 2 = -3 ").unwrap(), (2, -3));
+        for patch in &["1", "=4", "1=", "-1=4", "1=x", "1=2=3"] {
+            match parse_patch(patch) {
+                Err(ArgsError::InvalidPatch(p)) => assert_eq!(&p, patch),
+                other => panic!("Unexpected result for {}: {:?}", patch, other),
+            }
+        }
+    }
+}
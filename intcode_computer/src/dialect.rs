@@ -0,0 +1,190 @@
+//! Intcode dialects: named sets of extra instructions layered on the standard ones
+//!
+//! The opcode of an instruction is the value modulo 100, the upper digits holding the
+//! parameter modes, so every dialect shares the opcode space 0 to 99. The standard
+//! instruction set uses opcodes 1 to 9 and 99: an extension can claim any other opcode
+//! by registering a builder with [`Dialect::with_instruction`]. A builder is called
+//! once per interpreter the dialect is installed on, so that instructions keeping some
+//! state don't share it between machines.
+//!
+//! Built-in profiles, selectable by name with [`Dialect::from_name`]:
+//! - `standard`: no extension
+//! - `debug`: debug-print (opcode 97)
+//! - `extended`: debug-print (opcode 97) and halt-with-code (opcode 98)
+
+use crate::instruction::{Instruction, InstructionResult, InvalidInstruction};
+use crate::interpreter::Interpreter;
+use std::fmt;
+
+/// Opcodes used by the standard instruction set
+pub const STANDARD_OPCODES: [u8; 10] = [1, 2, 3, 4, 5, 6, 7, 8, 9, 99];
+
+/// Opcode of the debug-print extension
+pub const DEBUG_PRINT_OPCODE: u8 = 97;
+
+/// Opcode of the halt-with-code extension
+pub const HALT_WITH_CODE_OPCODE: u8 = 98;
+
+type InstructionBuilder = Box<dyn Fn() -> Instruction>;
+
+/// Named set of extra instructions
+pub struct Dialect {
+    name: String,
+    extensions: Vec<(u8, InstructionBuilder)>,
+}
+
+impl fmt::Debug for Dialect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Dialect")
+            .field("name", &self.name)
+            .field(
+                "opcodes",
+                &self
+                    .extensions
+                    .iter()
+                    .map(|(op, _)| *op)
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+/// Debug-print: writes its single parameter to stderr, with its address if it has one
+pub fn debug_print() -> Instruction {
+    Instruction::new(DEBUG_PRINT_OPCODE, 1, |params| {
+        match params[0].address() {
+            Ok(address) => eprintln!("[debug] [{}] = {}", address, params[0].read()?),
+            Err(_) => eprintln!("[debug] {}", params[0].read()?),
+        }
+        Ok(InstructionResult::Continue)
+    })
+    .unwrap()
+}
+
+/// Halt-with-code: stops the program, its single parameter becoming the exit code
+pub fn halt_with_code() -> Instruction {
+    Instruction::new(HALT_WITH_CODE_OPCODE, 1, |params| {
+        Ok(InstructionResult::Halt(params[0].read()?))
+    })
+    .unwrap()
+}
+
+impl Dialect {
+    pub fn new(name: &str) -> Dialect {
+        Dialect {
+            name: name.to_owned(),
+            extensions: Vec::new(),
+        }
+    }
+
+    pub fn standard() -> Dialect {
+        Dialect::new("standard")
+    }
+
+    /// Built-in dialect profile with the given name
+    pub fn from_name(name: &str) -> Option<Dialect> {
+        match name {
+            "standard" => Some(Dialect::standard()),
+            "debug" => Some(
+                Dialect::new("debug")
+                    .with_instruction(DEBUG_PRINT_OPCODE, debug_print)
+                    .unwrap(),
+            ),
+            "extended" => Some(
+                Dialect::new("extended")
+                    .with_instruction(DEBUG_PRINT_OPCODE, debug_print)
+                    .unwrap()
+                    .with_instruction(HALT_WITH_CODE_OPCODE, halt_with_code)
+                    .unwrap(),
+            ),
+            _ => None,
+        }
+    }
+
+    /// Register an extension instruction, built by `builder` for every interpreter
+    pub fn with_instruction<F>(
+        mut self,
+        opcode: u8,
+        builder: F,
+    ) -> Result<Dialect, InvalidInstruction>
+    where
+        F: 'static + Fn() -> Instruction,
+    {
+        if opcode > 99 {
+            return Err(InvalidInstruction::InvalidOpcode(opcode));
+        }
+        if STANDARD_OPCODES.contains(&opcode) || self.extensions.iter().any(|(op, _)| *op == opcode)
+        {
+            return Err(InvalidInstruction::ReservedOpcode(opcode));
+        }
+        self.extensions.push((opcode, Box::new(builder)));
+        Ok(self)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn opcodes(&self) -> impl Iterator<Item = u8> + '_ {
+        self.extensions.iter().map(|(op, _)| *op)
+    }
+
+    /// Add the extension instructions to an interpreter and mark it as using this dialect
+    pub fn install(&self, interpreter: &mut Interpreter) -> Result<(), InvalidInstruction> {
+        for (opcode, builder) in &self.extensions {
+            let instruction = builder();
+            if instruction.opcode() != *opcode {
+                return Err(InvalidInstruction::InvalidOpcode(instruction.opcode()));
+            }
+            interpreter.add_instruction(instruction);
+        }
+        interpreter.set_dialect_name(&self.name);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::interpreter::InterpreterError;
+    use crate::program::Program;
+
+    fn run(program: &str, dialect: &Dialect) -> Result<Interpreter, InterpreterError> {
+        let program = program.parse::<Program>().unwrap();
+        let mut interpreter =
+            Interpreter::complete_with_dialect(program, dialect, || 0, |_| ()).unwrap();
+        interpreter.execute()?;
+        Ok(interpreter)
+    }
+
+    #[test]
+    fn halt_with_code() {
+        let extended = Dialect::from_name("extended").unwrap();
+        let interpreter = run("1101,20,22,9,97,9,98,9,0,0", &extended).unwrap();
+        assert_eq!(interpreter.exit_code(), Some(42));
+
+        let interpreter = run("1101,20,22,7,99,0,0,0", &extended).unwrap();
+        assert_eq!(interpreter.exit_code(), None);
+    }
+
+    #[test]
+    fn unknown_opcode_reports_dialect() {
+        let debug = Dialect::from_name("debug").unwrap();
+        match run("10498,0", &debug) {
+            Err(InterpreterError::UnknownOpcode(98, dialect)) => assert_eq!(dialect, "debug"),
+            r => panic!("Unexpected result: {:?}", r.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn reserved_opcodes() {
+        assert!(Dialect::new("broken")
+            .with_instruction(4, debug_print)
+            .is_err());
+        assert!(Dialect::from_name("debug")
+            .unwrap()
+            .with_instruction(DEBUG_PRINT_OPCODE, debug_print)
+            .is_err());
+        assert!(Dialect::from_name("unknown").is_none());
+    }
+}
@@ -13,6 +13,9 @@ pub enum InstructionResult {
     JumpTo(usize),
     Quit,
     UpdateRelativeOffset(isize),
+    Halt(isize),
+    /// Pause the interpreter without executing the instruction, to retry it later
+    Block,
 }
 
 impl fmt::Debug for Instruction {
@@ -27,6 +30,7 @@ impl fmt::Debug for Instruction {
 #[derive(Debug)]
 pub enum InvalidInstruction {
     InvalidOpcode(u8),
+    ReservedOpcode(u8),
 }
 
 impl std::error::Error for InvalidInstruction {}
@@ -35,6 +39,7 @@ impl fmt::Display for InvalidInstruction {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             InvalidInstruction::InvalidOpcode(op) => write!(f, "Invalid opcode: {}", op),
+            InvalidInstruction::ReservedOpcode(op) => write!(f, "Opcode already in use: {}", op),
         }
     }
 }
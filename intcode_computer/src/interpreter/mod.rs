@@ -1,7 +1,8 @@
-use crate::instruction::{Instruction, InstructionResult};
+use crate::dialect::Dialect;
+use crate::instruction::{Instruction, InstructionResult, InvalidInstruction};
 use crate::parameter::{Parameter, ParameterError};
 use crate::program::Program;
-use std::cell::RefCell;
+use std::cell::{Ref, RefCell};
 use std::collections::HashMap;
 use std::convert::TryInto;
 use std::fmt;
@@ -13,14 +14,19 @@ pub struct Interpreter {
     instruction_pointer: usize,
     instructions: HashMap<u8, Instruction>,
     relative_offset: isize,
+    steps: usize,
+    step_limit: Option<usize>,
+    dialect: String,
+    exit_code: Option<isize>,
 }
 
 #[derive(Debug)]
 pub enum InterpreterError {
     InvalidOpcode(isize),
-    UnknownOpcode(u8),
+    UnknownOpcode(u8, String),
     InvalidParameter(ParameterError),
     UnexpectedEndOfFile,
+    StepLimitReached(usize),
 }
 
 impl std::error::Error for InterpreterError {}
@@ -30,12 +36,23 @@ impl fmt::Display for InterpreterError {
         use InterpreterError::*;
         match self {
             InvalidOpcode(op) => write!(f, "Invalid opcode found in program: {}", op),
-            UnknownOpcode(op) => write!(f, "Now instruction provided for opcode {}", op),
+            UnknownOpcode(op, dialect) => write!(
+                f,
+                "No instruction provided for opcode {} in dialect {}",
+                op, dialect
+            ),
             InvalidParameter(e) => write!(f, "{}", e),
             UnexpectedEndOfFile => write!(
                 f,
                 "Interpreter reached end-of-file without encountering a stop instruction (99)"
             ),
+            StepLimitReached(limit) => {
+                write!(
+                    f,
+                    "Interpreter stopped after reaching the step limit ({})",
+                    limit
+                )
+            }
         }
     }
 }
@@ -57,6 +74,25 @@ fn number_to_digits(n: usize) -> Vec<u8> {
 enum InterpreterState {
     End,
     Continue,
+    Blocked,
+}
+
+/// Memory and registers of a machine, to resume its execution later
+#[derive(Debug, Clone)]
+pub struct MachineState {
+    pub memory: Program,
+    pub instruction_pointer: usize,
+    pub relative_offset: isize,
+    pub steps: usize,
+}
+
+/// Why the interpreter stopped executing instructions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    /// The program reached a stop instruction
+    Halted,
+    /// An instruction could not proceed yet, it will be retried when execution resumes
+    Blocked,
 }
 
 impl Interpreter {
@@ -66,14 +102,92 @@ impl Interpreter {
             instruction_pointer: 0,
             instructions: HashMap::new(),
             relative_offset: 0,
+            steps: 0,
+            step_limit: None,
+            dialect: String::from("custom"),
+            exit_code: None,
         }
     }
 
+    /// Name of the dialect reported when an unknown opcode is encountered
+    pub fn set_dialect_name(&mut self, name: &str) {
+        self.dialect = name.to_owned();
+    }
+
+    pub fn dialect_name(&self) -> &str {
+        &self.dialect
+    }
+
+    /// Code given to a halt-with-code instruction, if the program stopped that way
+    pub fn exit_code(&self) -> Option<isize> {
+        self.exit_code
+    }
+
+    /// Limit the total number of instructions the interpreter may execute
+    pub fn set_step_limit(&mut self, step_limit: Option<usize>) {
+        self.step_limit = step_limit;
+    }
+
+    /// Number of instructions executed so far
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Current state of the program memory
+    pub fn program(&self) -> Ref<'_, Program> {
+        self.program.borrow()
+    }
+
+    /// Copy of the program memory, which stays valid while the interpreter keeps running
+    pub fn memory_snapshot(&self) -> Program {
+        self.program.borrow().clone()
+    }
+
+    /// Copy of the memory and registers, to be restored with `restore_state`
+    pub fn save_state(&self) -> MachineState {
+        MachineState {
+            memory: self.memory_snapshot(),
+            instruction_pointer: self.instruction_pointer,
+            relative_offset: self.relative_offset,
+            steps: self.steps,
+        }
+    }
+
+    /// Put the machine back in a saved state, the instructions being kept
+    pub fn restore_state(&mut self, state: MachineState) {
+        *self.program.borrow_mut() = state.memory;
+        self.instruction_pointer = state.instruction_pointer;
+        self.relative_offset = state.relative_offset;
+        self.steps = state.steps;
+        self.exit_code = None;
+    }
+
+    /// Consume the interpreter and give back the program memory
+    pub fn into_program(self) -> Program {
+        match Rc::try_unwrap(self.program) {
+            Ok(program) => program.into_inner(),
+            Err(program) => program.borrow().clone(),
+        }
+    }
+
+    pub fn instruction_pointer(&self) -> usize {
+        self.instruction_pointer
+    }
+
+    pub fn relative_offset(&self) -> isize {
+        self.relative_offset
+    }
+
     pub fn add_instruction(&mut self, instruction: Instruction) {
         self.instructions.insert(instruction.opcode(), instruction);
     }
 
     fn execute_one(&mut self) -> Result<InterpreterState, InterpreterError> {
+        if let Some(limit) = self.step_limit {
+            if self.steps >= limit {
+                return Err(InterpreterError::StepLimitReached(limit));
+            }
+        }
         let v = self
             .program
             .borrow()
@@ -108,8 +222,18 @@ impl Interpreter {
                     )
                 })
                 .collect::<Result<Vec<_>, _>>()?;
+            self.steps += 1;
             match instruction.call(parameters)? {
                 InstructionResult::Quit => return Ok(InterpreterState::End),
+                InstructionResult::Block => {
+                    // The instruction will be executed again, don't count it twice
+                    self.steps -= 1;
+                    return Ok(InterpreterState::Blocked);
+                }
+                InstructionResult::Halt(code) => {
+                    self.exit_code = Some(code);
+                    return Ok(InterpreterState::End);
+                }
                 InstructionResult::Continue => {
                     self.instruction_pointer += 1 + instruction.arguments_number() as usize;
                 }
@@ -125,39 +249,50 @@ impl Interpreter {
                 Ok(InterpreterState::Continue)
             }
         } else {
-            Err(InterpreterError::UnknownOpcode(opcode as u8))
+            Err(InterpreterError::UnknownOpcode(
+                opcode,
+                self.dialect.clone(),
+            ))
         }
     }
 
     pub fn execute(&mut self) -> Result<(), InterpreterError> {
-        while self.execute_one()? != InterpreterState::End {
-            // dbg!(&self);
-            // let mut line = String::new();
-            // std::io::stdin().read_line(&mut line).unwrap();
-        }
+        self.run()?;
         Ok(())
     }
 
-    pub fn complete<F, G>(program: Program, mut input_fn: F, mut output_fn: G) -> Interpreter
+    /// Execute instructions until the program halts or an instruction blocks
+    pub fn run(&mut self) -> Result<StopReason, InterpreterError> {
+        loop {
+            match self.execute_one()? {
+                InterpreterState::Continue => {}
+                InterpreterState::End => return Ok(StopReason::Halted),
+                InterpreterState::Blocked => return Ok(StopReason::Blocked),
+            }
+        }
+    }
+
+    pub fn complete<F, G>(program: Program, input_fn: F, output_fn: G) -> Interpreter
     where
         F: FnMut() -> isize + 'static,
         G: FnMut(isize) + 'static,
     {
-        let mut interpreter = Interpreter::new(program);
-
-        // Instruction: add
-        interpreter.add_instruction(Instruction::new(1, 3, |mut params| {
-            let sum = params[0].read()? + params[1].read()?;
-            params[2].write(sum)?;
-            Ok(InstructionResult::Continue)
-        }).unwrap());
+        Interpreter::complete_with_dialect(program, &Dialect::standard(), input_fn, output_fn)
+            .unwrap()
+    }
 
-        // Instruction: mulitply
-        interpreter.add_instruction(Instruction::new(2, 3, |mut params| {
-            let product = params[0].read()? * params[1].read()?;
-            params[2].write(product)?;
-            Ok(InstructionResult::Continue)
-        }).unwrap());
+    /// Interpreter with the standard instruction set and the extensions of `dialect`
+    pub fn complete_with_dialect<F, G>(
+        program: Program,
+        dialect: &Dialect,
+        mut input_fn: F,
+        mut output_fn: G,
+    ) -> Result<Interpreter, InvalidInstruction>
+    where
+        F: FnMut() -> isize + 'static,
+        G: FnMut(isize) + 'static,
+    {
+        let mut interpreter = Interpreter::without_io(program, dialect)?;
 
         // Instruction: input
         interpreter.add_instruction(Instruction::new(3, 1, move |mut params| {
@@ -173,6 +308,31 @@ impl Interpreter {
             Ok(InstructionResult::Continue)
         }).unwrap());
 
+        Ok(interpreter)
+    }
+
+    /// Interpreter with the standard instruction set and the extensions of `dialect`,
+    /// except for input (3) and output (4) which the caller adds with `add_instruction`
+    pub fn without_io(
+        program: Program,
+        dialect: &Dialect,
+    ) -> Result<Interpreter, InvalidInstruction> {
+        let mut interpreter = Interpreter::new(program);
+
+        // Instruction: add
+        interpreter.add_instruction(Instruction::new(1, 3, |mut params| {
+            let sum = params[0].read()? + params[1].read()?;
+            params[2].write(sum)?;
+            Ok(InstructionResult::Continue)
+        }).unwrap());
+
+        // Instruction: mulitply
+        interpreter.add_instruction(Instruction::new(2, 3, |mut params| {
+            let product = params[0].read()? * params[1].read()?;
+            params[2].write(product)?;
+            Ok(InstructionResult::Continue)
+        }).unwrap());
+
         // Interpreter: quit
         interpreter.add_instruction(Instruction::new(99, 0, |_| Ok(InstructionResult::Quit)).unwrap() );
 
@@ -221,6 +381,40 @@ impl Interpreter {
             Ok(InstructionResult::UpdateRelativeOffset(new_offset))
         }).unwrap());
 
-        interpreter
+        dialect.install(&mut interpreter)?;
+        Ok(interpreter)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn save_and_restore() {
+        // Counts up in [12] forever, outputting it through the relative base
+        let program = "109,5,1001,12,1,12,204,7,1105,1,2,99,0"
+            .parse::<Program>()
+            .unwrap();
+        let outputs = Rc::new(RefCell::new(Vec::new()));
+        let mut interpreter = Interpreter::complete(program, || 0, {
+            let outputs = outputs.clone();
+            move |output| outputs.borrow_mut().push(output)
+        });
+        interpreter.set_step_limit(Some(4));
+        assert!(interpreter.run().is_err());
+        let saved = interpreter.save_state();
+        assert_eq!(saved.relative_offset, 5);
+        assert_eq!(saved.steps, 4);
+
+        interpreter.set_step_limit(Some(8));
+        assert!(interpreter.run().is_err());
+        assert_eq!(*outputs.borrow(), vec![1, 2]);
+
+        interpreter.restore_state(saved);
+        assert_eq!(interpreter.instruction_pointer(), 2);
+        assert_eq!(interpreter.program()[12], 1);
+        assert!(interpreter.run().is_err());
+        assert_eq!(*outputs.borrow(), vec![1, 2, 2]);
     }
 }
@@ -1,9 +1,16 @@
 mod program;
+mod dialect;
 mod instruction;
 mod parameter;
 mod interpreter;
+mod memory;
+mod async_interpreter;
 
 pub use self::program::{Program, OutOfBoundError, ParseProgramError};
 pub use self::instruction::{Instruction, InvalidInstruction, InstructionResult};
-pub use self::parameter::{Parameter, ParameterError};
-pub use self::interpreter::{Interpreter, InterpreterError};
+pub use self::parameter::{Parameter, ParameterError, ParameterMode};
+pub use self::interpreter::{Interpreter, InterpreterError, MachineState, StopReason};
+pub use self::memory::{diff, HexDump, MemoryChange};
+pub use self::dialect::{debug_print, halt_with_code, Dialect};
+pub use self::dialect::{DEBUG_PRINT_OPCODE, HALT_WITH_CODE_OPCODE, STANDARD_OPCODES};
+pub use self::async_interpreter::{AsyncInterpreter, AsyncInterpreterError};
@@ -0,0 +1,150 @@
+use crate::parameter::ParameterMode;
+use crate::program::Program;
+use std::fmt;
+
+/// Mnemonic and number of arguments of the standard Intcode instructions
+const MNEMONICS: [(usize, &str, usize); 10] = [
+    (1, "add", 3),
+    (2, "mul", 3),
+    (3, "in", 1),
+    (4, "out", 1),
+    (5, "jnz", 2),
+    (6, "jz", 2),
+    (7, "lt", 3),
+    (8, "eq", 3),
+    (9, "arb", 1),
+    (99, "halt", 0),
+];
+
+/// Hexdump-style rendering of a memory image, annotating the instructions it can decode
+///
+/// Memory is decoded linearly from address 0: every cell that looks like a standard
+/// instruction is printed with its arguments on its own row, other cells are grouped
+/// into data rows.
+pub struct HexDump<'a> {
+    program: &'a Program,
+    width: usize,
+}
+
+struct DecodedInstruction {
+    mnemonic: &'static str,
+    modes: Vec<ParameterMode>,
+}
+
+fn decode(value: isize) -> Option<DecodedInstruction> {
+    if value < 0 {
+        return None;
+    }
+    let value = value as usize;
+    let (_, mnemonic, arguments_number) = MNEMONICS.iter().find(|(op, _, _)| *op == value % 100)?;
+    let mut modes = Vec::new();
+    let mut access_modes = value / 100;
+    for _ in 0..*arguments_number {
+        modes.push(ParameterMode::from_digit((access_modes % 10) as u8).ok()?);
+        access_modes /= 10;
+    }
+    if access_modes != 0 {
+        return None;
+    }
+    Some(DecodedInstruction { mnemonic, modes })
+}
+
+fn format_operand(mode: ParameterMode, value: isize) -> String {
+    match mode {
+        ParameterMode::Position => format!("[{}]", value),
+        ParameterMode::Immediate => value.to_string(),
+        ParameterMode::Relative if value < 0 => format!("[rb{}]", value),
+        ParameterMode::Relative => format!("[rb+{}]", value),
+    }
+}
+
+impl HexDump<'_> {
+    pub fn new(program: &Program) -> HexDump<'_> {
+        HexDump { program, width: 8 }
+    }
+
+    /// Maximum number of values printed on a data row
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = width.max(1);
+        self
+    }
+
+    fn write_row(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        address: usize,
+        values: &[isize],
+        annotation: &str,
+    ) -> fmt::Result {
+        write!(f, "{:06}:", address)?;
+        for value in values {
+            write!(f, " {:>8}", value)?;
+        }
+        if annotation.is_empty() {
+            writeln!(f)
+        } else {
+            let padding = 9 * (self.width.max(4) - values.len());
+            writeln!(f, "{:padding$}  {}", "", annotation, padding = padding)
+        }
+    }
+}
+
+impl fmt::Display for HexDump<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let memory = &self.program.memory;
+        let mut address = 0;
+        let mut data_start = 0;
+        while address < memory.len() {
+            let instruction =
+                decode(memory[address]).filter(|i| address + i.modes.len() < memory.len());
+            if let Some(instruction) = instruction {
+                if data_start < address {
+                    for (i, row) in memory[data_start..address].chunks(self.width).enumerate() {
+                        self.write_row(f, data_start + i * self.width, row, "")?;
+                    }
+                }
+                let end = address + 1 + instruction.modes.len();
+                let operands = instruction
+                    .modes
+                    .iter()
+                    .zip(&memory[(address + 1)..end])
+                    .map(|(mode, value)| format_operand(*mode, *value))
+                    .collect::<Vec<_>>();
+                let annotation = format!("{} {}", instruction.mnemonic, operands.join(", "));
+                self.write_row(f, address, &memory[address..end], annotation.trim_end())?;
+                address = end;
+                data_start = end;
+            } else {
+                address += 1;
+            }
+        }
+        for (i, row) in memory[data_start..].chunks(self.width).enumerate() {
+            self.write_row(f, data_start + i * self.width, row, "")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn annotated_dump() {
+        let program = "1,9,10,3,1002,4,3,4,204,-1,99,30,40,50"
+            .parse::<Program>()
+            .unwrap();
+        let dump = HexDump::new(&program).width(4).to_string();
+        let lines = dump.lines().map(|l| l.trim_end()).collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            vec![
+                "000000:        1        9       10        3  add [9], [10], [3]",
+                "000004:     1002        4        3        4  mul [4], 3, [4]",
+                "000008:      204       -1                    out [rb-1]",
+                "000010:       99                             halt",
+                "000011:       30       40       50",
+            ]
+        );
+    }
+}
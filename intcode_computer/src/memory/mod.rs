@@ -0,0 +1,69 @@
+use crate::program::Program;
+use std::cmp;
+use std::fmt;
+
+mod dump;
+
+pub use dump::HexDump;
+
+/// A single memory cell whose value differs between two memory images
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryChange {
+    pub address: usize,
+    pub before: isize,
+    pub after: isize,
+}
+
+impl fmt::Display for MemoryChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:>6}: {} -> {}", self.address, self.before, self.after)
+    }
+}
+
+/// Compare two memory images, cells missing from the shorter one being read as 0
+pub fn diff(before: &Program, after: &Program) -> Vec<MemoryChange> {
+    let len = cmp::max(before.len(), after.len());
+    (0..len)
+        .map(|address| MemoryChange {
+            address,
+            before: before.read(address).unwrap_or(0),
+            after: after.read(address).unwrap_or(0),
+        })
+        .filter(|change| change.before != change.after)
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn diff_memory() {
+        let before = "1,9,10,3,2,3,11,0,99,30,40,50".parse::<Program>().unwrap();
+        let after = "3500,9,10,70,2,3,11,0,99,30,40,50,0,7"
+            .parse::<Program>()
+            .unwrap();
+        let changes = diff(&before, &after);
+        assert_eq!(
+            changes,
+            vec![
+                MemoryChange {
+                    address: 0,
+                    before: 1,
+                    after: 3500
+                },
+                MemoryChange {
+                    address: 3,
+                    before: 3,
+                    after: 70
+                },
+                MemoryChange {
+                    address: 13,
+                    before: 0,
+                    after: 7
+                },
+            ]
+        );
+        assert_eq!(changes[0].to_string(), "     0: 1 -> 3500");
+    }
+}
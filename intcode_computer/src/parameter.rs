@@ -12,7 +12,8 @@ pub struct Parameter {
     relative_offset: isize,
 }
 
-#[derive(Debug)]
+/// How the raw value of a parameter is interpreted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ParameterMode {
     Position,
     Immediate,
@@ -24,6 +25,8 @@ pub enum ParameterError {
     OutOfBound(OutOfBoundError),
     IncompatibleMode,
     InvalidMode(u8),
+    NoAddress,
+    NegativeAddress(isize),
 }
 
 impl std::error::Error for ParameterError {}
@@ -34,6 +37,8 @@ impl fmt::Display for ParameterError {
             ParameterError::OutOfBound(e) => write!(f, "{}", e),
             ParameterError::IncompatibleMode => write!(f, "Immediate mode used to write value"),
             ParameterError::InvalidMode(m) => write!(f, "Invalid mode: {}", m),
+            ParameterError::NoAddress => write!(f, "Immediate mode parameter has no address"),
+            ParameterError::NegativeAddress(a) => write!(f, "Negative address: {}", a),
         }
     }
 }
@@ -44,6 +49,17 @@ impl From<OutOfBoundError> for ParameterError {
     }
 }
 
+impl ParameterMode {
+    pub fn from_digit(mode: u8) -> Result<ParameterMode, ParameterError> {
+        match mode {
+            0 => Ok(ParameterMode::Position),
+            1 => Ok(ParameterMode::Immediate),
+            2 => Ok(ParameterMode::Relative),
+            _ => Err(ParameterError::InvalidMode(mode)),
+        }
+    }
+}
+
 impl Parameter {
     pub fn new(
         mode: u8,
@@ -51,46 +67,97 @@ impl Parameter {
         program: Rc<RefCell<Program>>,
         relative_offset: isize,
     ) -> Result<Parameter, ParameterError> {
-        let mode = match mode {
-            0 => ParameterMode::Position,
-            1 => ParameterMode::Immediate,
-            2 => ParameterMode::Relative,
-            _ => return Err(ParameterError::InvalidMode(mode)),
-        };
         Ok(Parameter {
-            mode,
+            mode: ParameterMode::from_digit(mode)?,
             value,
             program,
             relative_offset,
         })
     }
+
+    pub fn mode(&self) -> ParameterMode {
+        self.mode
+    }
+
+    /// Operand as found in the program, before the mode is applied
+    pub fn raw_value(&self) -> isize {
+        self.value
+    }
+
+    /// Relative base in effect when the instruction was decoded
+    pub fn relative_offset(&self) -> isize {
+        self.relative_offset
+    }
+
+    /// Memory address designated by the parameter, which immediate parameters don't have
+    pub fn address(&self) -> Result<usize, ParameterError> {
+        let address = match self.mode {
+            ParameterMode::Immediate => return Err(ParameterError::NoAddress),
+            ParameterMode::Position => self.value,
+            ParameterMode::Relative => self.value + self.relative_offset,
+        };
+        if address < 0 {
+            Err(ParameterError::NegativeAddress(address))
+        } else {
+            Ok(address as usize)
+        }
+    }
+
     pub fn read(&self) -> Result<isize, ParameterError> {
         match self.mode {
             ParameterMode::Immediate => Ok(self.value),
-            ParameterMode::Position => Ok(self.program.borrow_mut().read(self.value as usize)?),
-            ParameterMode::Relative => {
-                let address = (self.value + self.relative_offset) as usize;
-                Ok(self.program.borrow_mut().read(address)?)
-            },
+            _ => Ok(self.program.borrow().read(self.address()?)?),
         }
     }
 
     pub fn write(&mut self, value: isize) -> Result<(), ParameterError> {
         match self.mode {
             ParameterMode::Immediate => Err(ParameterError::IncompatibleMode),
-            ParameterMode::Position => {
-                self.program
-                    .borrow_mut()
-                    .write(self.value as usize, value)?;
-                Ok(())
-            },
-            ParameterMode::Relative => {
-                let address = (self.value + self.relative_offset) as usize;
-                self.program
-                    .borrow_mut()
-                    .write(address, value)?;
+            _ => {
+                self.program.borrow_mut().write(self.address()?, value)?;
                 Ok(())
             }
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parameter(mode: u8, value: isize) -> Parameter {
+        let program = "10,20,30,40".parse::<Program>().unwrap();
+        Parameter::new(mode, value, Rc::new(RefCell::new(program)), 2).unwrap()
+    }
+
+    #[test]
+    fn addressing() {
+        let position = parameter(0, 1);
+        assert_eq!(position.mode(), ParameterMode::Position);
+        assert_eq!(position.raw_value(), 1);
+        assert_eq!(position.address().unwrap(), 1);
+        assert_eq!(position.read().unwrap(), 20);
+
+        let relative = parameter(2, -1);
+        assert_eq!(relative.mode(), ParameterMode::Relative);
+        assert_eq!(relative.address().unwrap(), 1);
+        assert_eq!(relative.read().unwrap(), 20);
+
+        let immediate = parameter(1, 3);
+        assert_eq!(immediate.read().unwrap(), 3);
+        assert!(matches!(
+            immediate.address(),
+            Err(ParameterError::NoAddress)
+        ));
+
+        let negative = parameter(2, -3);
+        assert!(matches!(
+            negative.address(),
+            Err(ParameterError::NegativeAddress(-1))
+        ));
+        assert!(matches!(
+            Parameter::new(3, 0, position.program.clone(), 0),
+            Err(ParameterError::InvalidMode(3))
+        ));
+    }
+}
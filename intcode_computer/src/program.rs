@@ -30,7 +30,7 @@ impl FromStr for Program {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let memory = match s
-            .split(",")
+            .split(',')
             .map(|s| s.trim().parse::<isize>())
             .collect::<Result<Vec<_>, _>>()
         {
@@ -41,17 +41,30 @@ impl FromStr for Program {
     }
 }
 
+impl fmt::Display for Program {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut values = self.memory.iter();
+        if let Some(first) = values.next() {
+            write!(f, "{}", first)?;
+        }
+        for value in values {
+            write!(f, ",{}", value)?;
+        }
+        Ok(())
+    }
+}
+
 impl Index<usize> for Program {
     type Output = isize;
 
     fn index(&self, index: usize) -> &Self::Output {
-        return &self.memory[index];
+        &self.memory[index]
     }
 }
 
 impl IndexMut<usize> for Program {
     fn index_mut(&mut self, index: usize) -> &mut Self::Output {
-        return &mut self.memory[index];
+        &mut self.memory[index]
     }
 }
 
@@ -88,6 +101,10 @@ impl Program {
         self.memory.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.memory.is_empty()
+    }
+
     pub fn from_stdin() -> Result<Program, Box<dyn std::error::Error>> {
         let mut s = String::new();
         std::io::stdin().read_to_string(&mut s)?;
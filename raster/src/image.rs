@@ -0,0 +1,191 @@
+use std::fmt;
+
+pub type Pos = (isize, isize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pixel {
+    Off,
+    On,
+    Transparent,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImageError {
+    InvalidDigit(char),
+    IncompleteLayer(usize),
+    EmptyLayer,
+}
+
+impl std::error::Error for ImageError {}
+
+impl fmt::Display for ImageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImageError::InvalidDigit(c) => write!(f, "Invalid pixel digit {:?}", c),
+            ImageError::IncompleteLayer(n) => {
+                write!(f, "Last layer is incomplete, only {} pixels", n)
+            }
+            ImageError::EmptyLayer => write!(f, "Layers must contain at least one pixel"),
+        }
+    }
+}
+
+/// Dense image whose top-left pixel is at `origin`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    origin: Pos,
+    pixels: Vec<Pixel>,
+}
+
+impl Image {
+    /// Blank image with its top-left pixel at (0, 0)
+    pub fn new(width: usize, height: usize) -> Image {
+        Image {
+            width,
+            height,
+            origin: (0, 0),
+            pixels: vec![Pixel::Off; width * height],
+        }
+    }
+
+    /// Smallest image holding every given pixel, the missing ones being off
+    pub fn from_sparse<I: IntoIterator<Item = (Pos, bool)>>(pixels: I) -> Image {
+        let pixels = pixels.into_iter().collect::<Vec<_>>();
+        let min_x = pixels.iter().map(|((x, _), _)| *x).min();
+        let min_y = pixels.iter().map(|((_, y), _)| *y).min();
+        let max_x = pixels.iter().map(|((x, _), _)| *x).max();
+        let max_y = pixels.iter().map(|((_, y), _)| *y).max();
+        let (min_x, min_y, max_x, max_y) = match (min_x, min_y, max_x, max_y) {
+            (Some(min_x), Some(min_y), Some(max_x), Some(max_y)) => (min_x, min_y, max_x, max_y),
+            _ => return Image::new(0, 0),
+        };
+        let mut image = Image::new((max_x - min_x + 1) as usize, (max_y - min_y + 1) as usize);
+        image.origin = (min_x, min_y);
+        for (pos, on) in pixels {
+            image.set(pos, if on { Pixel::On } else { Pixel::Off });
+        }
+        image
+    }
+
+    /// Stack layers of digits, first layer on top: 0 is off, 1 is on and 2 is transparent
+    pub fn from_layers(digits: &str, width: usize, height: usize) -> Result<Image, ImageError> {
+        let size = width * height;
+        if size == 0 {
+            return Err(ImageError::EmptyLayer);
+        }
+        let digits = digits
+            .chars()
+            .map(|c| match c {
+                '0' => Ok(Pixel::Off),
+                '1' => Ok(Pixel::On),
+                '2' => Ok(Pixel::Transparent),
+                _ => Err(ImageError::InvalidDigit(c)),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if digits.len() % size != 0 {
+            return Err(ImageError::IncompleteLayer(digits.len() % size));
+        }
+        let mut image = Image::new(width, height);
+        image.pixels = vec![Pixel::Transparent; size];
+        for layer in digits.chunks(size) {
+            for (pixel, layer_pixel) in image.pixels.iter_mut().zip(layer) {
+                if *pixel == Pixel::Transparent {
+                    *pixel = *layer_pixel;
+                }
+            }
+        }
+        Ok(image)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Position of the top-left pixel
+    pub fn origin(&self) -> Pos {
+        self.origin
+    }
+
+    fn index(&self, (x, y): Pos) -> Option<usize> {
+        let column = x.checked_sub(self.origin.0)?;
+        let row = y.checked_sub(self.origin.1)?;
+        if column < 0 || row < 0 || column as usize >= self.width || row as usize >= self.height {
+            return None;
+        }
+        Some(row as usize * self.width + column as usize)
+    }
+
+    /// Pixel at a position, off outside of the image
+    pub fn get(&self, pos: Pos) -> Pixel {
+        self.index(pos).map_or(Pixel::Off, |i| self.pixels[i])
+    }
+
+    /// Set a pixel, ignored outside of the image
+    pub fn set(&mut self, pos: Pos, pixel: Pixel) {
+        if let Some(i) = self.index(pos) {
+            self.pixels[i] = pixel;
+        }
+    }
+
+    /// Rows of pixels from top to bottom
+    pub fn rows(&self) -> impl Iterator<Item = &[Pixel]> {
+        self.pixels.chunks(self.width.max(1))
+    }
+}
+
+impl fmt::Display for Image {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for pixel in row {
+                match pixel {
+                    Pixel::Off => write!(f, " ")?,
+                    Pixel::On => write!(f, "\u{2588}")?,
+                    Pixel::Transparent => write!(f, "?")?,
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn negative_coordinates() {
+        let image = Image::from_sparse(vec![((-2, -1), true), ((1, 0), true), ((0, -1), false)]);
+        assert_eq!(image.origin(), (-2, -1));
+        assert_eq!((image.width(), image.height()), (4, 2));
+        assert_eq!(image.get((-2, -1)), Pixel::On);
+        assert_eq!(image.get((1, 0)), Pixel::On);
+        assert_eq!(image.get((-1, 0)), Pixel::Off);
+        assert_eq!(image.get((5, 5)), Pixel::Off);
+        assert_eq!(image.to_string(), "\u{2588}   \n   \u{2588}\n");
+    }
+
+    #[test]
+    fn layers() {
+        let image = Image::from_layers("0222112222120000", 2, 2).unwrap();
+        assert_eq!(image.to_string(), " \u{2588}\n\u{2588} \n");
+        assert_eq!(
+            Image::from_layers("0122", 2, 1).unwrap().to_string(),
+            " \u{2588}\n"
+        );
+        assert_eq!(
+            Image::from_layers("012", 2, 1),
+            Err(ImageError::IncompleteLayer(1))
+        );
+        assert_eq!(
+            Image::from_layers("0132", 2, 1),
+            Err(ImageError::InvalidDigit('3'))
+        );
+    }
+}
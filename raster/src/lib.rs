@@ -0,0 +1,103 @@
+//! Black and white images rendered to text, Netpbm and SVG
+//!
+//! Images are built either from sparse pixels keyed by position, which may be
+//! negative, or from the layered digit format of the Space Image Format. Text
+//! drawn with the 4x6 capital letters can be read back with [`recognize`].
+
+mod image;
+mod netpbm;
+mod ocr;
+mod svg;
+
+pub use self::image::{Image, ImageError, Pixel, Pos};
+pub use self::netpbm::{to_pbm, to_pgm};
+pub use self::ocr::{recognize, OcrError, UnknownGlyph};
+pub use self::svg::to_svg;
+
+use std::path::Path;
+
+/// Scale and margin applied when rendering an image to a file format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RenderOptions {
+    scale: usize,
+    margin: usize,
+}
+
+impl Default for RenderOptions {
+    fn default() -> RenderOptions {
+        RenderOptions {
+            scale: 1,
+            margin: 0,
+        }
+    }
+}
+
+impl RenderOptions {
+    pub fn new() -> RenderOptions {
+        RenderOptions::default()
+    }
+
+    /// Side of a rendered pixel, in file pixels
+    pub fn with_scale(mut self, scale: usize) -> RenderOptions {
+        self.scale = scale.max(1);
+        self
+    }
+
+    /// Blank border around the image, in image pixels
+    pub fn with_margin(mut self, margin: usize) -> RenderOptions {
+        self.margin = margin;
+        self
+    }
+
+    pub fn scale(&self) -> usize {
+        self.scale
+    }
+
+    pub fn margin(&self) -> usize {
+        self.margin
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Pbm,
+    Pgm,
+    Svg,
+}
+
+impl Format {
+    /// Format matching the extension of a file name
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Format> {
+        let extension = path.as_ref().extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "pbm" => Some(Format::Pbm),
+            "pgm" => Some(Format::Pgm),
+            "svg" => Some(Format::Svg),
+            _ => None,
+        }
+    }
+}
+
+impl Image {
+    pub fn render(&self, format: Format, options: &RenderOptions) -> String {
+        match format {
+            Format::Pbm => to_pbm(self, options),
+            Format::Pgm => to_pgm(self, options),
+            Format::Svg => to_svg(self, options),
+        }
+    }
+
+    /// Write the image to a file, in the format given by its extension
+    pub fn save<P: AsRef<Path>>(&self, path: P, options: &RenderOptions) -> std::io::Result<()> {
+        let format = Format::from_path(&path).ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!(
+                    "Unknown image format for {}, expected .pbm, .pgm or .svg",
+                    path.as_ref().display()
+                ),
+            )
+        })?;
+        std::fs::write(path, self.render(format, options))
+    }
+}
@@ -0,0 +1,106 @@
+use crate::image::{Image, Pixel};
+use crate::RenderOptions;
+
+/// Plain Netpbm files should not have lines longer than 70 characters
+const MAX_LINE_LENGTH: usize = 70;
+
+/// Rows of the image once scaled and surrounded by its margin
+fn raster(image: &Image, options: &RenderOptions) -> (usize, usize, Vec<Vec<Pixel>>) {
+    let scale = options.scale();
+    let margin = options.margin();
+    let width = (image.width() + 2 * margin) * scale;
+    let height = (image.height() + 2 * margin) * scale;
+    let blank = vec![Pixel::Off; width];
+    let mut rows = vec![blank.clone(); margin * scale];
+    for row in image.rows().take(image.height()) {
+        let mut line = vec![Pixel::Off; margin * scale];
+        for pixel in row {
+            line.extend(std::iter::repeat_n(*pixel, scale));
+        }
+        line.resize(width, Pixel::Off);
+        rows.extend(std::iter::repeat_n(line, scale));
+    }
+    rows.resize(height, blank);
+    (width, height, rows)
+}
+
+/// Append the samples of a row, wrapping lines that grow too long
+fn write_samples<I: Iterator<Item = String>>(s: &mut String, samples: I, separator: &str) {
+    let mut line_length = 0;
+    for sample in samples {
+        if line_length > 0 && line_length + separator.len() + sample.len() > MAX_LINE_LENGTH {
+            s.push('\n');
+            line_length = 0;
+        } else if line_length > 0 {
+            s.push_str(separator);
+            line_length += separator.len();
+        }
+        line_length += sample.len();
+        s.push_str(&sample);
+    }
+    s.push('\n');
+}
+
+/// Plain PBM (P1) bitmap, pixels that are on drawn in black
+pub fn to_pbm(image: &Image, options: &RenderOptions) -> String {
+    let (width, height, rows) = raster(image, options);
+    let mut s = format!("P1\n{} {}\n", width, height);
+    for row in rows {
+        let samples = row.iter().map(|pixel| match pixel {
+            Pixel::On => "1".to_string(),
+            _ => "0".to_string(),
+        });
+        write_samples(&mut s, samples, "");
+    }
+    s
+}
+
+/// Plain PGM (P2) graymap, pixels that are on drawn in black and transparent ones in gray
+pub fn to_pgm(image: &Image, options: &RenderOptions) -> String {
+    let (width, height, rows) = raster(image, options);
+    let mut s = format!("P2\n{} {}\n255\n", width, height);
+    for row in rows {
+        let samples = row.iter().map(|pixel| match pixel {
+            Pixel::On => "0".to_string(),
+            Pixel::Off => "255".to_string(),
+            Pixel::Transparent => "128".to_string(),
+        });
+        write_samples(&mut s, samples, " ");
+    }
+    s
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn bitmap() {
+        let image = Image::from_sparse(vec![((-1, 0), true), ((0, 0), false)]);
+        assert_eq!(to_pbm(&image, &RenderOptions::new()), "P1\n2 1\n10\n");
+        let options = RenderOptions::new().with_scale(2).with_margin(1);
+        assert_eq!(
+            to_pbm(&image, &options),
+            "P1\n8 6\n00000000\n00000000\n00110000\n00110000\n00000000\n00000000\n"
+        );
+    }
+
+    #[test]
+    fn graymap() {
+        let image = Image::from_layers("0212", 3, 1);
+        assert!(image.is_err());
+        let image = Image::from_layers("012222", 3, 1).unwrap();
+        assert_eq!(
+            to_pgm(&image, &RenderOptions::new()),
+            "P2\n3 1\n255\n255 0 128\n"
+        );
+    }
+
+    #[test]
+    fn long_lines() {
+        let image = Image::from_sparse((0..100).map(|x| ((x, 0), x % 2 == 0)));
+        let pbm = to_pbm(&image, &RenderOptions::new());
+        assert!(pbm.lines().all(|line| line.len() <= MAX_LINE_LENGTH));
+        assert_eq!(pbm.lines().skip(2).collect::<String>().len(), 100);
+    }
+}
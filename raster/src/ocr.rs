@@ -0,0 +1,186 @@
+use crate::image::{Image, Pixel};
+use std::fmt;
+
+/// Capital letters of the 4x6 font, '#' for the pixels that are on
+const LETTERS: &[(char, [&str; 6])] = &[
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', [".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', ["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+/// Glyph that does not match any known letter
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownGlyph {
+    /// Position of the glyph in the recognized text
+    pub index: usize,
+    /// First image column of the glyph
+    pub column: usize,
+    /// Rows of the glyph, '#' for the pixels that are on
+    pub bitmap: Vec<String>,
+}
+
+/// Text read with '?' in place of the glyphs that could not be recognized
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OcrError {
+    pub text: String,
+    pub unknown: Vec<UnknownGlyph>,
+}
+
+impl std::error::Error for OcrError {}
+
+impl fmt::Display for OcrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Unrecognized glyphs in {:?}", self.text)?;
+        for glyph in &self.unknown {
+            write!(f, "\nglyph {} at column {}:", glyph.index, glyph.column)?;
+            for row in &glyph.bitmap {
+                write!(f, "\n{}", row)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Remove the columns with no pixel on from both sides of a glyph
+fn trim_columns(rows: &[String]) -> Vec<String> {
+    let lit = |c: usize| rows.iter().any(|row| row.as_bytes().get(c) == Some(&b'#'));
+    let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    match (0..width).find(|c| lit(*c)) {
+        Some(first) => {
+            let last = (0..width).rev().find(|c| lit(*c)).unwrap();
+            rows.iter()
+                .map(|row| row.get(first..=last).unwrap_or("").to_string())
+                .collect()
+        }
+        None => Vec::new(),
+    }
+}
+
+fn letter(bitmap: &[String]) -> Option<char> {
+    LETTERS.iter().find_map(|(c, rows)| {
+        let rows = rows.iter().map(|row| row.to_string()).collect::<Vec<_>>();
+        if trim_columns(&rows) == bitmap {
+            Some(*c)
+        } else {
+            None
+        }
+    })
+}
+
+/// Read the capital letters drawn in an image
+///
+/// Glyphs are separated by columns with no pixel on, and the rows above and
+/// below the text are ignored. Transparent pixels count as off.
+pub fn recognize(image: &Image) -> Result<String, OcrError> {
+    let (x0, y0) = image.origin();
+    let on = |column: usize, row: usize| {
+        image.get((x0 + column as isize, y0 + row as isize)) == Pixel::On
+    };
+    let rows = (0..image.height())
+        .filter(|row| (0..image.width()).any(|column| on(column, *row)))
+        .collect::<Vec<_>>();
+    let (top, bottom) = match (rows.first(), rows.last()) {
+        (Some(top), Some(bottom)) => (*top, *bottom),
+        _ => return Ok(String::new()),
+    };
+
+    let mut text = String::new();
+    let mut unknown = Vec::new();
+    let mut column = 0;
+    while column < image.width() {
+        let lit = |column: usize| (top..=bottom).any(|row| on(column, row));
+        if !lit(column) {
+            column += 1;
+            continue;
+        }
+        let start = column;
+        while column < image.width() && lit(column) {
+            column += 1;
+        }
+        let bitmap = (top..=bottom)
+            .map(|row| {
+                (start..column)
+                    .map(|c| if on(c, row) { '#' } else { '.' })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>();
+        match letter(&bitmap) {
+            Some(c) => text.push(c),
+            None => {
+                unknown.push(UnknownGlyph {
+                    index: text.chars().count(),
+                    column: start,
+                    bitmap,
+                });
+                text.push('?');
+            }
+        }
+    }
+    if unknown.is_empty() {
+        Ok(text)
+    } else {
+        Err(OcrError { text, unknown })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn image(rows: &[&str]) -> Image {
+        Image::from_sparse(rows.iter().enumerate().flat_map(|(y, row)| {
+            row.chars()
+                .enumerate()
+                .map(move |(x, c)| ((x as isize, y as isize), c == '#'))
+        }))
+    }
+
+    #[test]
+    fn letters() {
+        let image = image(&[
+            "...............................",
+            ".####.#....###....##.####.###..",
+            "....#.#....#..#....#.#....#..#.",
+            "...#..#....###.....#.###..#..#.",
+            "..#...#....#..#....#.#....###..",
+            ".#....#....#..#.#..#.#....#....",
+            ".####.####.###...##..#....#....",
+        ]);
+        assert_eq!(recognize(&image), Ok("ZLBJFP".to_string()));
+        assert_eq!(recognize(&Image::new(3, 3)), Ok(String::new()));
+    }
+
+    #[test]
+    fn unknown_glyph() {
+        let image = image(&[
+            "#..#.#...#",
+            "#..#.##.##",
+            "####.#.#.#",
+            "#..#.#...#",
+            "#..#.#...#",
+            "#..#.#...#",
+        ]);
+        let error = recognize(&image).unwrap_err();
+        assert_eq!(error.text, "H?");
+        assert_eq!(error.unknown.len(), 1);
+        assert_eq!(error.unknown[0].index, 1);
+        assert_eq!(error.unknown[0].column, 5);
+        assert_eq!(error.unknown[0].bitmap[1], "##.##");
+        assert!(error.to_string().ends_with("#.#.#\n#...#\n#...#\n#...#"));
+    }
+}
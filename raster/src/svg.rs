@@ -0,0 +1,67 @@
+use crate::image::{Image, Pixel};
+use crate::RenderOptions;
+use std::fmt::Write;
+
+/// SVG document drawing runs of pixels as black rectangles, transparent ones in gray
+pub fn to_svg(image: &Image, options: &RenderOptions) -> String {
+    let scale = options.scale();
+    let margin = options.margin();
+    let width = (image.width() + 2 * margin) * scale;
+    let height = (image.height() + 2 * margin) * scale;
+    let mut s = String::new();
+    writeln!(
+        s,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" viewBox="0 0 {0} {1}" shape-rendering="crispEdges">"#,
+        width, height
+    )
+    .unwrap();
+    writeln!(
+        s,
+        r#"<rect width="{}" height="{}" fill="white"/>"#,
+        width, height
+    )
+    .unwrap();
+    for (y, row) in image.rows().take(image.height()).enumerate() {
+        let mut x = 0;
+        while x < row.len() {
+            let pixel = row[x];
+            let run = row[x..].iter().take_while(|p| **p == pixel).count();
+            let fill = match pixel {
+                Pixel::On => Some("black"),
+                Pixel::Transparent => Some("gray"),
+                Pixel::Off => None,
+            };
+            if let Some(fill) = fill {
+                writeln!(
+                    s,
+                    r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}"/>"#,
+                    (x + margin) * scale,
+                    (y + margin) * scale,
+                    run * scale,
+                    scale,
+                    fill
+                )
+                .unwrap();
+            }
+            x += run;
+        }
+    }
+    s.push_str("</svg>\n");
+    s
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn runs() {
+        let image = Image::from_sparse(vec![((0, 0), true), ((1, 0), true), ((3, 1), true)]);
+        let svg = to_svg(&image, &RenderOptions::new().with_scale(10).with_margin(1));
+        assert!(svg.contains(r#"width="60" height="40" viewBox="0 0 60 40""#));
+        assert!(svg.contains(r#"<rect x="10" y="10" width="20" height="10" fill="black"/>"#));
+        assert!(svg.contains(r#"<rect x="40" y="20" width="10" height="10" fill="black"/>"#));
+        assert_eq!(svg.matches("<rect").count(), 3);
+        assert!(svg.ends_with("</svg>\n"));
+    }
+}